@@ -5,6 +5,11 @@ use ndarray::{Array, Array1, Array3, Axis, Dimension, Zip};
 use num::complex::Complex64;
 use rayon::prelude::*;
 use rustfft::{Fft, FftPlanner};
+use split_operator::{
+    grid::Grid,
+    propagator::{fft_transformation::FFTTransformation, transformation::Transformation},
+    wave_function::WaveFunction,
+};
 
 fn assign_iter<N: Dimension>(
     a: &mut Array<Complex64, N>,
@@ -112,6 +117,24 @@ fn fft_lane_map(c: &mut Criterion) {
             let _d = assign_iter_with(&mut matrix.clone(), &fft, 0);
         })
     });
+
+    let grids = vec![
+        Grid::new_linear_countable("x", 0.0, 1.0, 1024, 0),
+        Grid::new_linear_countable("y", 0.0, 1.0, 160, 1),
+        Grid::new_linear_countable("z", 0.0, 1.0, 3, 2),
+    ];
+    let mut wave_function = WaveFunction::new(matrix.clone().into_dyn(), grids.clone());
+
+    for axis in [0, 2] {
+        let mut transformation = FFTTransformation::new(&grids[axis], "k");
+
+        c.bench_function(&format!("fft_transformation_axis_{axis}"), |c| {
+            c.iter(|| {
+                transformation.transform(&mut wave_function);
+                transformation.inverse_transform(&mut wave_function);
+            })
+        });
+    }
 }
 
 criterion_group!(
@@ -1,87 +1,236 @@
 use std::f64::consts::PI;
 
-use ndarray::Array1;
+use ndarray::{Array1, ArrayD, IxDyn};
 use num::complex::Complex64;
 
 use crate::{
     control::Control,
     grid::Grid,
     loss_checker::LossChecker,
-    propagator::{one_dim_propagator::OneDimPropagator, Propagator},
+    propagator::{n_dim_propagator::NDimPropagator, one_dim_propagator::OneDimPropagator, Propagator},
+    report::LossReport,
+    time_grid::TimeGrid,
     wave_function::WaveFunction,
 };
 
+/// Returns value of the sine mask at distance `depth` from the border of the grid.
+fn sine_mask(depth: f64, mask_width: f64, mask_end: f64) -> f64 {
+    if depth > mask_width {
+        1.0
+    } else if depth < mask_end {
+        0.0
+    } else {
+        (PI / 2.0 * depth / mask_width).sin()
+    }
+}
+
+/// Creates sine mask damping the wave function near the end of the grid.
 pub fn dumping_end(mask_width: f64, mask_end: f64, grid: &Grid) -> Array1<Complex64> {
     let r_max = grid.nodes.last().unwrap();
 
-    let dumping = grid
-        .nodes
-        .clone()
-        .into_iter()
-        .map(|x| {
-            if x < r_max - mask_width {
-                Complex64::from(1.0)
-            } else if x > r_max - mask_end {
-                Complex64::from(0.0)
-            } else {
-                Complex64::from((PI / 2.0 * (r_max - x) / mask_width).sin())
-            }
-        })
-        .collect::<Vec<Complex64>>();
+    grid.nodes
+        .iter()
+        .map(|x| Complex64::from(sine_mask(r_max - x, mask_width, mask_end)))
+        .collect()
+}
+
+/// Creates sine mask damping the wave function near the start of the grid.
+pub fn dumping_start(mask_width: f64, mask_end: f64, grid: &Grid) -> Array1<Complex64> {
+    let r_min = grid.nodes.first().unwrap();
+
+    grid.nodes
+        .iter()
+        .map(|x| Complex64::from(sine_mask(x - r_min, mask_width, mask_end)))
+        .collect()
+}
+
+/// Creates sine mask damping the wave function near both ends of the grid.
+pub fn dumping_both(mask_width: f64, mask_end: f64, grid: &Grid) -> Array1<Complex64> {
+    dumping_start(mask_width, mask_end, grid) * dumping_end(mask_width, mask_end, grid)
+}
+
+/// Creates n dimensional mask on the `grids` with values given by the `mask` closure
+/// that takes the coordinates of a grid point in the order of the dimensions.
+pub fn region_mask<F>(grids: &[Grid], mask: F) -> ArrayD<Complex64>
+where
+    F: Fn(&[f64]) -> f64,
+{
+    let shape: Vec<usize> = grids.iter().map(|grid| grid.nodes_no).collect();
+    let mut mask_array = ArrayD::<Complex64>::zeros(IxDyn(&shape));
+    let mut coordinates = vec![0.0; grids.len()];
+
+    for (index, value) in mask_array.indexed_iter_mut() {
+        for (i, coordinate) in coordinates.iter_mut().enumerate() {
+            *coordinate = grids[i].nodes[index[i]];
+        }
+
+        *value = Complex64::from(mask(&coordinates));
+    }
+
+    mask_array
+}
+
+#[derive(Clone)]
+enum Mask {
+    OneDim(OneDimPropagator),
+    NDim(NDimPropagator),
+}
+
+/// Single absorbing region of [`BorderDumping`] with its own loss checking.
+/// `base_mask` is the mask for the reference time step.
+#[derive(Clone)]
+struct DumpingRegion {
+    mask: Mask,
+    base_mask: ArrayD<Complex64>,
+    loss_checked: Option<LossChecker>,
+}
+
+impl DumpingRegion {
+    fn new(mask: Mask, base_mask: ArrayD<Complex64>, loss_checked: Option<LossChecker>) -> Self {
+        Self { mask, base_mask, loss_checked }
+    }
+
+    /// Sets the mask to the base mask raised to the power `exponent`.
+    fn scale_mask(&mut self, exponent: f64) {
+        let mask = self.base_mask.mapv(|x| x.powf(exponent));
 
-    Array1::from(dumping)
+        match &mut self.mask {
+            Mask::OneDim(operator) => operator.set_operator(mask.into_dimensionality().unwrap()),
+            Mask::NDim(operator) => operator.set_operator(mask),
+        }
+    }
+
+    fn apply(&mut self, wave_function: &mut WaveFunction, time: f64) {
+        if let Some(loss_checker) = &mut self.loss_checked {
+            loss_checker.check_before(wave_function);
+        }
+
+        match &mut self.mask {
+            Mask::OneDim(operator) => operator.apply(wave_function, time),
+            Mask::NDim(operator) => operator.apply(wave_function, time),
+        }
+
+        if let Some(loss_checker) = &mut self.loss_checked {
+            loss_checker.check_after(wave_function, time);
+        }
+    }
 }
 
+/// Damps the wave function using masks on absorbing regions.
+/// Masks can be placed on several axes and on arbitrary n dimensional regions,
+/// each named region has its own [`LossChecker`], accessible by `region_loss_checker_mut`, so that the loss is attributed separately to each region.
+/// Masks are applied in the order they were added.
+///
+/// When the [`TimeGrid`] changes, masks are scaled as mask^(|dt / dt0|) so that the damping rate per unit time is kept,
+/// where dt0 is the reference time step set by `set_reference_step` or the time step before the first change.
+/// Masks keep damping also in backward propagation.
 #[derive(Clone)]
 pub struct BorderDumping {
-    operator: OneDimPropagator,
+    regions: Vec<DumpingRegion>,
+    reference_step: Option<f64>,
     loss_checked: Option<LossChecker>,
 }
 
 impl BorderDumping {
+    /// Creates new `BorderDumping` with single mask acting on the given grid.
     pub fn new(mask: Array1<Complex64>, grid: &Grid) -> Self {
         let mut operator = OneDimPropagator::new(mask.len(), grid.dimension_no);
-        operator.set_operator(mask);
+        operator.set_operator(mask.clone());
 
         BorderDumping {
-            operator,
+            regions: vec![DumpingRegion::new(Mask::OneDim(operator), mask.into_dyn(), None)],
+            reference_step: None,
             loss_checked: None,
         }
     }
 
+    /// Creates new `BorderDumping` without any masks.
+    pub fn empty() -> Self {
+        BorderDumping {
+            regions: Vec::new(),
+            reference_step: None,
+            loss_checked: None,
+        }
+    }
+
+    /// Adds absorbing region with given `name` defined by one dimensional mask acting on the given grid.
+    pub fn add_mask(&mut self, name: &str, mask: Array1<Complex64>, grid: &Grid) {
+        let mut operator = OneDimPropagator::new(mask.len(), grid.dimension_no);
+        operator.set_operator(mask.clone());
+
+        self.regions.push(DumpingRegion::new(Mask::OneDim(operator), mask.into_dyn(), Some(LossChecker::new(name))));
+    }
+
+    /// Adds absorbing region with given `name` defined by n dimensional mask, see [`region_mask`].
+    pub fn add_n_dim_mask(&mut self, name: &str, mask: ArrayD<Complex64>) {
+        let mut operator = NDimPropagator::new();
+        operator.set_operator(mask.clone());
+
+        self.regions.push(DumpingRegion::new(Mask::NDim(operator), mask, Some(LossChecker::new(name))));
+    }
+
+    /// Sets the time step `step` for which the masks were created.
+    pub fn set_reference_step(&mut self, step: f64) {
+        self.reference_step = Some(step);
+    }
+
     pub fn add_loss_checker(&mut self, loss_checker: LossChecker) {
         self.loss_checked = Some(loss_checker);
     }
-}
 
-impl Control for BorderDumping {
-    fn name(&self) -> &str {
-        "BorderDumping"
+    /// Returns the [`LossChecker`] of the absorbing region with given `name`.
+    pub fn region_loss_checker(&self, name: &str) -> Option<&LossChecker> {
+        self.regions
+            .iter()
+            .filter_map(|region| region.loss_checked.as_ref())
+            .find(|loss| loss.name == name)
     }
 
-    fn first_half(&mut self, wave_function: &mut WaveFunction) {
-        if let Some(loss_checker) = &mut self.loss_checked {
-            loss_checker.check_before(wave_function);
-        }
-
-        self.operator.apply(wave_function);
+    /// Returns mutable [`LossChecker`] of the absorbing region with given `name`,
+    /// e.g. to add its [`LossDistribution`](crate::loss_distribution::LossDistribution) or to replace it by one with a loss saver.
+    pub fn region_loss_checker_mut(&mut self, name: &str) -> Option<&mut LossChecker> {
+        self.regions
+            .iter_mut()
+            .filter_map(|region| region.loss_checked.as_mut())
+            .find(|loss| loss.name == name)
+    }
 
-        if let Some(loss_checker) = &mut self.loss_checked {
-            loss_checker.check_after(wave_function);
+    /// Resets the cumulative losses of each named absorbing region.
+    pub fn reset_region_losses(&mut self) {
+        for region in &mut self.regions {
+            if let Some(loss_checker) = &mut region.loss_checked {
+                loss_checker.reset();
+            }
         }
     }
 
-    fn second_half(&mut self, wave_function: &mut WaveFunction) {
+    fn apply(&mut self, wave_function: &mut WaveFunction, time: f64) {
         if let Some(loss_checker) = &mut self.loss_checked {
             loss_checker.check_before(wave_function);
         }
 
-        self.operator.apply(wave_function);
+        for region in &mut self.regions {
+            region.apply(wave_function, time);
+        }
 
         if let Some(loss_checker) = &mut self.loss_checked {
-            loss_checker.check_after(wave_function);
+            loss_checker.check_after(wave_function, time);
         }
     }
+}
+
+impl Control for BorderDumping {
+    fn name(&self) -> &str {
+        "BorderDumping"
+    }
+
+    fn first_half(&mut self, wave_function: &mut WaveFunction, time: f64) {
+        self.apply(wave_function, time);
+    }
+
+    fn second_half(&mut self, wave_function: &mut WaveFunction, time: f64) {
+        self.apply(wave_function, time);
+    }
 
     fn loss(&self) -> &Option<LossChecker> {
         &self.loss_checked
@@ -90,4 +239,33 @@ impl Control for BorderDumping {
     fn loss_mut(&mut self) -> &mut Option<LossChecker> {
         &mut self.loss_checked
     }
+
+    fn loss_checkers_mut(&mut self) -> Vec<&mut LossChecker> {
+        self.loss_checked
+            .iter_mut()
+            .chain(self.regions.iter_mut().filter_map(|region| region.loss_checked.as_mut()))
+            .collect()
+    }
+
+    fn region_losses(&self) -> Vec<LossReport> {
+        self.regions
+            .iter()
+            .filter_map(|region| region.loss_checked.as_ref())
+            .map(|loss| LossReport {
+                name: loss.name.clone(),
+                loss: loss.cumulative_loss(),
+            })
+            .collect()
+    }
+
+    fn update_time_grid(&mut self, previous: &TimeGrid, time_grid: &TimeGrid) {
+        // previous time grid might not be set yet
+        let previous_step = if previous.step != 0.0 { previous.step } else { time_grid.step };
+        let reference_step = *self.reference_step.get_or_insert(previous_step);
+        let exponent = (time_grid.step / reference_step).abs();
+
+        for region in &mut self.regions {
+            region.scale_mask(exponent);
+        }
+    }
 }
@@ -1,29 +1,61 @@
 use crate::grid::Grid;
 
+/// Identity of a grid used to detect grid changes,
+/// `generation` is bumped whenever the grid could have been modified.
+#[derive(Clone, Copy, Default, PartialEq)]
+struct GridIdentity {
+    generation: usize,
+    nodes_no: usize,
+}
+
 /// Observes changes in the wave function such as possible norm change and grid change.
 /// Used inside `WaveFunction` to help calculate the norm of the wave function.
-/// Propagators and diagonalizations change `possible_norm_change` to true when they act on a wave function.
+/// `WaveFunction` invalidates the norm whenever its array or grids are accessed mutably
+/// and invalidates the grids whenever they are accessed mutably.
 #[derive(Clone, Default)]
 pub struct ChangeObserver {
-    last_grid_names: Vec<String>,
+    generation: usize,
+    grid_generations: Vec<usize>,
+    last_grids: Vec<GridIdentity>,
 
     last_norm: f64,
-    pub possible_norm_change: bool,
+    possible_norm_change: bool,
 }
 
 impl ChangeObserver {
     /// Creates new `ChangeObserver` with given grids.
-    pub fn new(grids: &Vec<Grid>) -> Self {
-        ChangeObserver {
-            last_grid_names: grids.into_iter().map(|x| x.name.clone()).collect(),
+    pub fn new(grids: &[Grid]) -> Self {
+        let mut change_observer = ChangeObserver {
             last_norm: 1.0,
             possible_norm_change: true,
-        }
+            ..Default::default()
+        };
+        change_observer.observe_grid(grids);
+
+        change_observer
     }
 
     /// Observes current new grids.
-    pub fn observe_grid(&mut self, new_grids: &Vec<Grid>) {
-        self.last_grid_names = new_grids.into_iter().map(|x| x.name.clone()).collect();
+    pub fn observe_grid(&mut self, new_grids: &[Grid]) {
+        self.grid_generations.resize(new_grids.len(), self.generation);
+        self.last_grids = new_grids.iter()
+            .enumerate()
+            .map(|(dimension_no, grid)| self.identity(dimension_no, grid))
+            .collect();
+    }
+
+    /// Marks the grid on dimension `dimension_no` as possibly modified.
+    pub fn invalidate_grid(&mut self, dimension_no: usize) {
+        self.generation += 1;
+        if let Some(generation) = self.grid_generations.get_mut(dimension_no) {
+            *generation = self.generation;
+        }
+    }
+
+    /// Marks all grids as possibly modified.
+    pub fn invalidate_grids(&mut self) {
+        self.generation += 1;
+        self.grid_generations.fill(self.generation);
     }
 
     /// Observes current new norm.
@@ -32,16 +64,35 @@ impl ChangeObserver {
         self.possible_norm_change = false;
     }
 
+    /// Marks the last observed norm as outdated.
+    pub fn invalidate_norm(&mut self) {
+        self.possible_norm_change = true;
+    }
+
+    /// Returns true if the norm could have changed since last observation using `observe_norm`.
+    pub fn possible_norm_change(&self) -> bool {
+        self.possible_norm_change
+    }
+
     /// Returns last observed norm.
     pub fn last_norm(&self) -> f64 {
         self.last_norm
     }
 
-    /// Returns true if grid has changed since last observation using `observe_grid`.
-    pub fn has_grid_changed(&self, grids: &Vec<Grid>) -> bool {
-        self.last_grid_names
-            .iter()
-            .zip(grids.into_iter().map(|x| x.name.clone()))
-            .any(|(x, y)| x != &y)
+    /// Returns true if any grid was invalidated or changed its size since last observation using `observe_grid`.
+    pub fn has_grid_changed(&self, grids: &[Grid]) -> bool {
+        self.last_grids.len() != grids.len()
+            || self.last_grids
+                .iter()
+                .zip(grids)
+                .enumerate()
+                .any(|(dimension_no, (last, grid))| *last != self.identity(dimension_no, grid))
+    }
+
+    fn identity(&self, dimension_no: usize, grid: &Grid) -> GridIdentity {
+        GridIdentity {
+            generation: self.grid_generations[dimension_no],
+            nodes_no: grid.nodes_no,
+        }
     }
 }
@@ -1,19 +1,32 @@
 use enum_flags::enum_flags;
 
-use crate::{loss_checker::LossChecker, wave_function::WaveFunction};
+use crate::{loss_checker::LossChecker, report::LossReport, time_grid::TimeGrid, wave_function::WaveFunction};
 
 /// Trait for controlling the wave function during propagation.
 pub trait Control {
     /// Returns the name of the control.
     fn name(&self) -> &str;
-    /// Checks and controls the wave function on the first half of the time step.
-    fn first_half(&mut self, wave_function: &mut WaveFunction);
-    /// Checks and controls the wave function on the second half of the time step.
-    fn second_half(&mut self, wave_function: &mut WaveFunction);
+    /// Checks and controls the wave function on the first half of the time step starting at `time`.
+    fn first_half(&mut self, wave_function: &mut WaveFunction, time: f64);
+    /// Checks and controls the wave function on the second half of the time step ending at `time`.
+    fn second_half(&mut self, wave_function: &mut WaveFunction, time: f64);
 
     fn loss(&self) -> &Option<LossChecker>;
 
     fn loss_mut(&mut self) -> &mut Option<LossChecker>;
+
+    /// Returns all loss checkers of the control, including the ones of separate regions.
+    fn loss_checkers_mut(&mut self) -> Vec<&mut LossChecker> {
+        self.loss_mut().iter_mut().collect()
+    }
+
+    /// Returns cumulative losses attributed to separate regions of the control, if any.
+    fn region_losses(&self) -> Vec<LossReport> {
+        Vec::new()
+    }
+
+    /// Adapts the control after the [`TimeGrid`] changed from `previous` to `time_grid`, by default does nothing.
+    fn update_time_grid(&mut self, _previous: &TimeGrid, _time_grid: &TimeGrid) {}
 }
 
 #[repr(u8)]
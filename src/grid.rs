@@ -1,3 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+use crate::special_functions::{gauss_hermite_quadrature, gauss_laguerre_quadrature, gauss_legendre_quadrature};
+
 /// General one dimensional grid. It is used to create a grid for a specific dimension.
 /// The grid contains:
 /// - `name`: name of the grid
@@ -10,7 +14,12 @@
 /// - `new_linear_continuos`: creates a grid with linearly spaced nodes and weights associated to continuous space
 /// - `new_linear_countable`: creates a grid with linearly spaced nodes and weights associated to countable space
 /// - `new_custom`: creates a grid with given custom nodes and weights
-#[derive(Clone, Default)]
+/// - `new_mapped`: creates a grid with nodes density proportional to given local wave number
+/// - `new_fattal_kosloff`: creates a mapped grid with local wave number estimated from the potential
+/// - `new_gauss_legendre`: creates a polar grid with Gauss-Legendre quadrature in cos θ
+/// - `new_gauss_hermite`: creates a grid with Gauss-Hermite quadrature nodes
+/// - `new_gauss_laguerre`: creates a grid with Gauss-Laguerre quadrature nodes
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Grid {
     pub name: String,
     pub dimension_no: usize,
@@ -83,6 +92,149 @@ impl Grid {
         }
     }
 
+    /// Creates a new grid with nodes r(x) mapped from uniform coordinate x with unit step,
+    /// such that the density of nodes is proportional to the `local_wave_number` k(r).
+    /// Weights are equal to the Jacobian dr/dx of the mapping.
+    pub fn new_mapped<F>(
+        name: &str,
+        start_position: f64,
+        end_position: f64,
+        nodes_no: usize,
+        dimension_no: usize,
+        local_wave_number: F,
+    ) -> Grid
+    where
+        F: Fn(f64) -> f64,
+    {
+        assert!(nodes_no > 1, "Mapped grid needs at least 2 nodes");
+        assert!(end_position > start_position, "End position has to be larger than start position");
+
+        let fine_no = 50 * nodes_no;
+        let fine_step = (end_position - start_position) / (fine_no as f64 - 1.0);
+        let fine_nodes: Vec<f64> = (0..fine_no)
+            .map(|i| start_position + fine_step * i as f64)
+            .collect();
+        let wave_numbers: Vec<f64> = fine_nodes.iter().map(|&r| local_wave_number(r)).collect();
+        assert!(
+            wave_numbers.iter().all(|&k| k > 0.0 && k.is_finite()),
+            "Local wave number has to be positive"
+        );
+
+        let mut cumulative = vec![0.0; fine_no];
+        for i in 1..fine_no {
+            cumulative[i] = cumulative[i - 1] + 0.5 * fine_step * (wave_numbers[i - 1] + wave_numbers[i]);
+        }
+        let scaling = cumulative[fine_no - 1] / (nodes_no as f64 - 1.0);
+
+        let mut nodes = Vec::with_capacity(nodes_no);
+        let mut fine_index = 1;
+        for i in 0..nodes_no {
+            let target = scaling * i as f64;
+            while fine_index < fine_no - 1 && cumulative[fine_index] < target {
+                fine_index += 1;
+            }
+            let (c_0, c_1) = (cumulative[fine_index - 1], cumulative[fine_index]);
+            let fraction = ((target - c_0) / (c_1 - c_0)).clamp(0.0, 1.0);
+
+            nodes.push(fine_nodes[fine_index - 1] + fraction * fine_step);
+        }
+        nodes[0] = start_position;
+        nodes[nodes_no - 1] = end_position;
+
+        let weights = nodes.iter().map(|&r| scaling / local_wave_number(r)).collect();
+
+        Grid {
+            name: name.to_string(),
+            dimension_no,
+            nodes_no,
+            nodes,
+            weights,
+        }
+    }
+
+    /// Creates a new mapped grid using Fattal-Kosloff mapping with local wave number estimated from the `potential`
+    /// and the maximal kinetic `energy` of the particle with given `mass`.
+    /// The estimate k(r) = sqrt(2 mass (energy + sqrt(V(r)^2 + energy^2))) follows both the oscillations in the wells
+    /// and the decay inside repulsive walls, while staying smooth as required by the FFT kinetic operator.
+    /// Since `energy` also sets the smoothing scale it should be comparable to the depth of the potential well.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_fattal_kosloff<F>(
+        name: &str,
+        start_position: f64,
+        end_position: f64,
+        nodes_no: usize,
+        dimension_no: usize,
+        potential: F,
+        mass: f64,
+        energy: f64,
+    ) -> Grid
+    where
+        F: Fn(f64) -> f64,
+    {
+        assert!(energy > 0.0, "Energy has to be positive");
+
+        Grid::new_mapped(name, start_position, end_position, nodes_no, dimension_no, |r| {
+            let potential = potential(r);
+
+            (2.0 * mass * (energy + (potential * potential + energy * energy).sqrt())).sqrt()
+        })
+    }
+
+    /// Creates a new polar grid with nodes θ in ascending order, such that cos θ are the nodes of Gauss-Legendre quadrature.
+    /// Weights integrate over sin θ dθ, so that Legendre transformations on this grid are unitary.
+    pub fn new_gauss_legendre(name: &str, nodes_no: usize, dimension_no: usize) -> Grid {
+        let (nodes, weights) = gauss_legendre_quadrature(nodes_no);
+
+        Grid {
+            name: name.to_string(),
+            dimension_no,
+            nodes_no,
+            nodes: nodes.iter().rev().map(|x| x.acos()).collect(),
+            weights: weights.into_iter().rev().collect(),
+        }
+    }
+
+    /// Creates a new grid with nodes `center + scale * x_i`, where x_i are the nodes of Gauss-Hermite quadrature.
+    /// Weights integrate over the grid coordinate without the Gaussian weight.
+    pub fn new_gauss_hermite(name: &str, nodes_no: usize, center: f64, scale: f64, dimension_no: usize) -> Grid {
+        assert!(scale > 0.0, "Scale has to be positive");
+        let (nodes, weights) = gauss_hermite_quadrature(nodes_no);
+
+        Grid {
+            name: name.to_string(),
+            dimension_no,
+            nodes_no,
+            nodes: nodes.iter().map(|x| center + scale * x).collect(),
+            weights: weights.iter().map(|w| scale * w).collect(),
+        }
+    }
+
+    /// Creates a new grid with nodes `start_position + scale * x_i`, where x_i are the nodes of Gauss-Laguerre quadrature.
+    /// Weights integrate over the grid coordinate without the exponential weight.
+    pub fn new_gauss_laguerre(name: &str, nodes_no: usize, start_position: f64, scale: f64, dimension_no: usize) -> Grid {
+        assert!(scale > 0.0, "Scale has to be positive");
+        let (nodes, weights) = gauss_laguerre_quadrature(nodes_no);
+
+        Grid {
+            name: name.to_string(),
+            dimension_no,
+            nodes_no,
+            nodes: nodes.iter().map(|x| start_position + scale * x).collect(),
+            weights: weights.iter().map(|w| scale * w).collect(),
+        }
+    }
+
+    /// Returns whether the nodes of the grid are linearly spaced, as required by kinetic operators applied by FFT,
+    /// which is not the case e.g. for mapped and quadrature grids.
+    pub fn is_uniform(&self) -> bool {
+        if self.nodes_no < 3 {
+            return true;
+        }
+
+        let step = (self.nodes[self.nodes_no - 1] - self.nodes[0]) / (self.nodes_no as f64 - 1.0);
+        self.nodes.windows(2).all(|x| ((x[1] - x[0]) - step).abs() <= 1e-8 * step.abs())
+    }
+
     /// Swaps two grids.
     pub fn swap(&mut self, other: &mut Grid) {
         std::mem::swap(self, other);
@@ -0,0 +1,436 @@
+use std::collections::BTreeMap;
+
+use ndarray::{Array1, Array2, ArrayD};
+use num::complex::Complex64;
+use quantum::particles::Particles;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    grid::Grid,
+    hamiltonian_factory::{
+        absorbing_potentials::AbsorbingPotential, hamiltonian_broadcasting::two_dim_into_n_dim_operator,
+        kinetic_operator::kinetic_hamiltonian, legendre_diagonalization::legendre_diagonalization_operator,
+        rotational_operator::rotational_hamiltonian,
+    },
+    loss_checker::LossChecker,
+    propagation::OperationStack,
+    propagator::{
+        fft_transformation::FFTTransformation,
+        mapped_kinetic_propagator::MappedKineticPropagator,
+        mapped_transformation::MappedTransformation,
+        n_dim_propagator::NDimPropagator,
+        non_diagonal_propagator::NonDiagPropagator,
+        one_dim_propagator::OneDimPropagator,
+        propagator_factory::{
+            absorbing_into_propagator, channel_matrices_into_propagator, complex_n_dim_into_propagator,
+            evaluate_on_grids, n_dim_into_propagator, one_dim_into_propagator,
+        },
+        transformation::{Order, Transformation},
+        Propagator,
+    },
+    time_grid::{TimeGrid, TimeStep},
+    wave_function::WaveFunction,
+};
+
+/// [`Transformation`] that can be cloned into operations of the [`OperationStack`].
+pub trait BasisTransformation: Transformation + Send {
+    fn boxed_clone(&self) -> Box<dyn BasisTransformation>;
+}
+
+impl<T: Transformation + Clone + Send + 'static> BasisTransformation for T {
+    fn boxed_clone(&self) -> Box<dyn BasisTransformation> {
+        Box::new(self.clone())
+    }
+}
+
+/// Identifier of a basis given by the transformation name and the grid names after the transformation.
+type BasisKey = (String, Vec<String>);
+
+/// Operator of the [`HamiltonianTerm`] in the basis it is diagonal or applied in.
+#[derive(Clone)]
+enum TermOperator {
+    OneDim { hamiltonian: Array1<f64>, grid: Grid },
+    Absorbing { potential: Array1<f64>, grid: Grid },
+    NDim(ArrayD<f64>),
+    ComplexNDim(ArrayD<Complex64>),
+    ChannelMatrix {
+        matrices: Vec<Array2<Complex64>>,
+        index_axes: Vec<usize>,
+        indices: ArrayD<usize>,
+        channel_dimension: usize,
+        hermitian: bool,
+    },
+    MappedKinetic(MappedKineticPropagator),
+}
+
+/// Operator multiplying the wave function in the uniform coordinate of the mapped grid by the kinetic energy.
+struct MappedKineticAction(MappedKineticPropagator);
+
+impl Propagator for MappedKineticAction {
+    fn name(&self) -> &str {
+        "MappedKineticAction"
+    }
+
+    fn apply(&mut self, wave_function: &mut WaveFunction, _time: f64) {
+        self.0.kinetic_action(wave_function);
+    }
+
+    fn loss(&self) -> &Option<LossChecker> {
+        self.0.loss()
+    }
+
+    fn loss_mut(&mut self) -> &mut Option<LossChecker> {
+        self.0.loss_mut()
+    }
+
+    fn loss_reset(&mut self) {}
+}
+
+impl TermOperator {
+    /// Returns operator multiplying the wave function by the hamiltonian.
+    fn action(&self) -> Box<dyn Propagator + Send> {
+        match self {
+            TermOperator::OneDim { hamiltonian, grid } => {
+                let mut operator = OneDimPropagator::new(grid.nodes_no, grid.dimension_no);
+                operator.set_operator(hamiltonian.mapv(Complex64::from));
+
+                Box::new(operator)
+            }
+            TermOperator::Absorbing { potential, grid } => {
+                let mut operator = OneDimPropagator::new(grid.nodes_no, grid.dimension_no);
+                operator.set_operator(potential.mapv(|x| -Complex64::i() * x));
+
+                Box::new(operator)
+            }
+            TermOperator::NDim(hamiltonian) => {
+                let mut operator = NDimPropagator::new();
+                operator.set_operator(hamiltonian.mapv(Complex64::from));
+
+                Box::new(operator)
+            }
+            TermOperator::ComplexNDim(hamiltonian) => {
+                let mut operator = NDimPropagator::new();
+                operator.set_operator(hamiltonian.clone());
+
+                Box::new(operator)
+            }
+            TermOperator::ChannelMatrix { matrices, index_axes, indices, channel_dimension, .. } => {
+                let mut operator = NonDiagPropagator::new(*channel_dimension);
+                operator.set_indexed_operators(matrices.clone(), index_axes.clone(), indices.clone());
+
+                Box::new(operator)
+            }
+            TermOperator::MappedKinetic(propagator) => Box::new(MappedKineticAction(propagator.clone())),
+        }
+    }
+
+    /// Returns propagator of the hamiltonian with given [`TimeGrid`] and [`TimeStep`].
+    fn propagator(&self, time_grid: &TimeGrid, step: TimeStep) -> Box<dyn Propagator + Send> {
+        match self {
+            TermOperator::OneDim { hamiltonian, grid } => {
+                Box::new(one_dim_into_propagator(hamiltonian.clone(), grid, time_grid, step))
+            }
+            TermOperator::Absorbing { potential, grid } => {
+                Box::new(absorbing_into_propagator(potential.clone(), grid, time_grid, step))
+            }
+            TermOperator::NDim(hamiltonian) => Box::new(n_dim_into_propagator(hamiltonian.clone(), time_grid, step)),
+            TermOperator::ComplexNDim(hamiltonian) => {
+                Box::new(complex_n_dim_into_propagator(hamiltonian.clone(), time_grid, step))
+            }
+            TermOperator::ChannelMatrix { matrices, index_axes, indices, channel_dimension, hermitian } => {
+                Box::new(channel_matrices_into_propagator(
+                    matrices.clone(),
+                    index_axes.clone(),
+                    indices.clone(),
+                    *channel_dimension,
+                    *hermitian,
+                    time_grid,
+                    step,
+                ))
+            }
+            TermOperator::MappedKinetic(propagator) => Box::new(propagator.with_step(step, time_grid)),
+        }
+    }
+}
+
+/// Term of the [`Hamiltonian`] given by an operator acting in the basis reached by `transformations`,
+/// e.g. kinetic term applied in the basis of [`FFTTransformation`].
+/// The same term is used both to evaluate H|ψ⟩ and to create its propagator,
+/// the operator multiplying by the hamiltonian is created once with the term.
+pub struct HamiltonianTerm {
+    name: String,
+    transformations: Vec<Box<dyn BasisTransformation>>,
+    operator: TermOperator,
+    action: Box<dyn Propagator + Send>,
+    parameters: BTreeMap<String, serde_json::Value>,
+}
+
+impl HamiltonianTerm {
+    fn new(name: &str, operator: TermOperator) -> Self {
+        Self {
+            name: name.to_string(),
+            transformations: Vec::new(),
+            action: operator.action(),
+            operator,
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    /// Creates term from one dimensional hamiltonian acting on given [`Grid`].
+    pub fn one_dim(name: &str, hamiltonian: Array1<f64>, grid: &Grid) -> Self {
+        assert!(hamiltonian.len() == grid.nodes_no, "Hamiltonian has to have the size of the grid");
+
+        Self::new(name, TermOperator::OneDim { hamiltonian, grid: grid.clone() })
+    }
+
+    /// Creates term from one dimensional absorbing potential W acting on given [`Grid`] as -iW.
+    pub fn absorbing(name: &str, potential: Array1<f64>, grid: &Grid) -> Self {
+        assert!(potential.len() == grid.nodes_no, "Absorbing potential has to have the size of the grid");
+
+        Self::new(name, TermOperator::Absorbing { potential, grid: grid.clone() })
+    }
+
+    /// Creates term from [`AbsorbingPotential`] placed on given [`Grid`],
+    /// its parameters are written to the description of the propagator in the [`RunManifest`](crate::manifest::RunManifest).
+    pub fn absorbing_potential(name: &str, absorbing_potential: &AbsorbingPotential, grid: &Grid) -> Self {
+        Self::absorbing(name, absorbing_potential.potential(grid), grid).with_parameters(absorbing_potential)
+    }
+
+    /// Creates term from n dimensional hamiltonian.
+    pub fn n_dim(name: &str, hamiltonian: ArrayD<f64>) -> Self {
+        Self::new(name, TermOperator::NDim(hamiltonian))
+    }
+
+    /// Creates term from n dimensional complex hamiltonian.
+    pub fn complex_n_dim(name: &str, hamiltonian: ArrayD<Complex64>) -> Self {
+        Self::new(name, TermOperator::ComplexNDim(hamiltonian))
+    }
+
+    /// Creates term from channel matrix `hamiltonian` acting on `channel_dimension` and depending on the nodes of `grids`.
+    /// Propagator of `hermitian` matrices is calculated using the eigendecomposition,
+    /// otherwise using the Padé approximant, e.g. for matrices with complex absorbing potential.
+    pub fn channel_matrix<F>(name: &str, hamiltonian: F, grids: &[&Grid], channel_dimension: usize, hermitian: bool) -> Self
+    where
+        F: Fn(&[f64]) -> Array2<Complex64> + Sync,
+    {
+        let (matrices, index_axes, indices) = evaluate_on_grids(hamiltonian, grids);
+
+        Self::new(name, TermOperator::ChannelMatrix { matrices, index_axes, indices, channel_dimension, hermitian })
+    }
+
+    /// Creates kinetic term along given [`Grid`] in the basis of [`FFTTransformation`].
+    pub fn kinetic(grid: &Grid, collision_params: &Particles) -> Self {
+        Self::one_dim(&format!("kinetic {}", grid.name), kinetic_hamiltonian(grid, collision_params), grid)
+            .in_basis(FFTTransformation::new(grid, &format!("{} momentum", grid.name)))
+            .with_parameters(json!({ "mass": collision_params.red_mass() }))
+    }
+
+    /// Creates kinetic term along mapped [`Grid`] created by [`Grid::new_mapped`]
+    /// in the uniform coordinate of the mapping reached by [`MappedTransformation`].
+    pub fn mapped_kinetic(grid: &Grid, collision_params: &Particles) -> Self {
+        let propagator = MappedKineticPropagator::new(grid, collision_params, &TimeGrid::default(), TimeStep::Full);
+
+        Self::new(&format!("kinetic {}", grid.name), TermOperator::MappedKinetic(propagator))
+            .in_basis(MappedTransformation::new(grid, &format!("{} mapped", grid.name)))
+            .with_parameters(json!({ "mass": collision_params.red_mass() }))
+    }
+
+    /// Creates rotational term on radial and polar grids in the basis of Legendre polynomials.
+    pub fn rotational(
+        example_wave_function: &WaveFunction,
+        radial_grid: &Grid,
+        polar_grid: &Grid,
+        collision_params: &Particles,
+        rotational_const: f64,
+    ) -> Self {
+        let hamiltonian = rotational_hamiltonian(radial_grid, polar_grid, collision_params, rotational_const);
+        let hamiltonian = two_dim_into_n_dim_operator(example_wave_function, hamiltonian, radial_grid, polar_grid);
+
+        Self::n_dim("rotational", hamiltonian).in_basis(legendre_diagonalization_operator(polar_grid))
+    }
+
+    /// Applies the term in the basis reached by `transformation` after the already added transformations.
+    pub fn in_basis<T: Transformation + Clone + Send + 'static>(mut self, transformation: T) -> Self {
+        self.transformations.push(Box::new(transformation));
+
+        self
+    }
+
+    /// Adds `parameters` of the term written to the description of its propagator,
+    /// see [`OperationStack::describe_last_operation`]. `parameters` have to serialize to JSON object.
+    pub fn with_parameters<T: Serialize>(mut self, parameters: T) -> Self {
+        let parameters = serde_json::to_value(parameters).expect("Parameters have to be serializable to JSON");
+        let serde_json::Value::Object(parameters) = parameters else {
+            panic!("Parameters have to serialize to JSON object");
+        };
+        self.parameters.extend(parameters);
+
+        self
+    }
+
+    /// Returns the name of the term.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Applies the term to `wave_function` returning to the original basis.
+    pub fn apply(&mut self, wave_function: &mut WaveFunction) {
+        for transformation in self.transformations.iter_mut() {
+            transformation.transform(wave_function);
+        }
+
+        self.action.apply(wave_function, 0.0);
+
+        for transformation in self.transformations.iter_mut().rev() {
+            transformation.inverse_transform(wave_function);
+        }
+    }
+
+    /// Returns identifiers of the bases reached by the transformations.
+    fn basis_keys(&self, example_wave_function: &WaveFunction) -> Vec<BasisKey> {
+        let mut wave_function = example_wave_function.clone();
+
+        self.transformations.iter()
+            .map(|transformation| {
+                let mut transformation = transformation.boxed_clone();
+                transformation.transform(&mut wave_function);
+
+                let grid_names = wave_function.grids().iter().map(|grid| grid.name.clone()).collect();
+                (transformation.name().to_string(), grid_names)
+            })
+            .collect()
+    }
+}
+
+/// Energy statistics of a wave function with respect to a [`Hamiltonian`].
+/// - `mean` is ⟨H⟩.
+/// - `variance` is ⟨H²⟩ - ⟨H⟩².
+/// - `residual` is the norm of (H - ⟨H⟩)|ψ⟩ relative to the norm of |ψ⟩.
+#[derive(Clone, Copy, Debug)]
+pub struct EnergyStatistics {
+    pub mean: f64,
+    pub variance: f64,
+    pub residual: f64,
+}
+
+/// Splitting scheme of the propagation step. Available options are:
+/// - `Strang` symmetric splitting with the term named `center` propagated by the full step in the center
+///   and other terms propagated by half steps in the order of addition.
+pub enum Splitting {
+    Strang { center: String },
+}
+
+/// Hamiltonian as a sum of [`HamiltonianTerm`], used to evaluate H|ψ⟩ without changing the propagated wave function
+/// and to generate the [`OperationStack`] of the split-operator propagation.
+#[derive(Default)]
+pub struct Hamiltonian {
+    terms: Vec<HamiltonianTerm>,
+}
+
+impl Hamiltonian {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_term(&mut self, term: HamiltonianTerm) {
+        self.terms.push(term);
+    }
+
+    /// Returns H|ψ⟩ calculated on copies of `wave_function`.
+    pub fn apply(&mut self, wave_function: &WaveFunction) -> WaveFunction {
+        assert!(!self.terms.is_empty(), "Hamiltonian has to have at least one term");
+
+        let mut result = wave_function.clone();
+        result.array_mut().fill(Complex64::from(0.0));
+
+        for term in self.terms.iter_mut() {
+            let mut term_result = wave_function.clone();
+            term.apply(&mut term_result);
+
+            *result.array_mut() += term_result.array();
+        }
+
+        result
+    }
+
+    /// Returns ⟨H⟩, energy variance and residual of `wave_function` without changing it.
+    pub fn energy_statistics(&mut self, wave_function: &WaveFunction) -> EnergyStatistics {
+        let mut state = wave_function.clone();
+        let mut h_state = self.apply(wave_function);
+
+        let norm = state.norm();
+        let mean = state.inner_product(&h_state).re / norm;
+        let h_squared = h_state.norm() / norm;
+
+        let mut residual = h_state;
+        residual.array_mut().scaled_add(Complex64::from(-mean), state.array());
+
+        EnergyStatistics {
+            mean,
+            variance: h_squared - mean * mean,
+            residual: (residual.norm() / norm).sqrt(),
+        }
+    }
+
+    /// Creates [`OperationStack`] propagating with given [`TimeGrid`] and [`Splitting`],
+    /// `example_wave_function` is used to track the basis changes between the terms.
+    pub fn operation_stack(&self, example_wave_function: &WaveFunction, time_grid: &TimeGrid, splitting: &Splitting) -> OperationStack {
+        let mut operation_stack = OperationStack::new();
+        self.append_to(&mut operation_stack, example_wave_function, time_grid, splitting);
+
+        operation_stack
+    }
+
+    /// Appends propagators of the terms and required transformations to `operation_stack` with given [`TimeGrid`] and [`Splitting`],
+    /// such that the center term is the last operation. Savers and controls should be added before.
+    pub fn append_to(
+        &self,
+        operation_stack: &mut OperationStack,
+        example_wave_function: &WaveFunction,
+        time_grid: &TimeGrid,
+        splitting: &Splitting,
+    ) {
+        let Splitting::Strang { center } = splitting;
+        let center_terms: Vec<usize> = (0..self.terms.len())
+            .filter(|&i| self.terms[i].name == *center)
+            .collect();
+        assert!(center_terms.len() == 1, "Center term has to match exactly one term name");
+
+        let mut order: Vec<usize> = (0..self.terms.len()).filter(|&i| i != center_terms[0]).collect();
+        order.push(center_terms[0]);
+
+        let mut wave_function = example_wave_function.clone();
+        let mut applied: Vec<(BasisKey, Box<dyn BasisTransformation>)> = Vec::new();
+
+        for &i in order.iter() {
+            let term = &self.terms[i];
+            let keys = term.basis_keys(example_wave_function);
+
+            let common = applied.iter()
+                .zip(keys.iter())
+                .take_while(|((applied_key, _), key)| applied_key == *key)
+                .count();
+
+            // Undo transformations using their copies in the transformed state
+            while applied.len() > common {
+                let (_, mut transformation) = applied.pop().unwrap();
+                operation_stack.add_transformation(transformation.boxed_clone(), Order::InverseFirst);
+                transformation.inverse_transform(&mut wave_function);
+            }
+
+            for (transformation, key) in term.transformations[common..].iter().zip(keys[common..].iter()) {
+                operation_stack.add_transformation(transformation.boxed_clone(), Order::Normal);
+
+                let mut transformation = transformation.boxed_clone();
+                transformation.transform(&mut wave_function);
+                applied.push((key.clone(), transformation));
+            }
+
+            let step = if i == center_terms[0] { TimeStep::Full } else { TimeStep::Half };
+            operation_stack.add_propagator(term.operator.propagator(time_grid, step));
+            operation_stack.name_last_operation(&term.name);
+            operation_stack.describe_last_operation(&term.parameters);
+        }
+    }
+}
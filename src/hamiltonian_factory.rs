@@ -1,3 +1,4 @@
+pub mod absorbing_potentials;
 pub mod analytic_potentials;
 pub mod hamiltonian_broadcasting;
 pub mod kinetic_operator;
@@ -0,0 +1,137 @@
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+
+use crate::grid::Grid;
+
+/// Constants of the transmission-free absorbing potential from D. E. Manolopoulos, J. Chem. Phys. 117, 9552 (2002).
+const MANOLOPOULOS_C: f64 = 2.62206;
+const MANOLOPOULOS_DELTA: f64 = 0.2;
+/// Fraction of the Manolopoulos layer at which the potential diverging at the outer edge is capped.
+const MANOLOPOULOS_EDGE: f64 = 0.99;
+
+/// Boundary of the grid on which the absorbing potential is placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Boundary {
+    Start,
+    End,
+    Both,
+}
+
+/// Form of the complex absorbing potential -iW(x). Available options are:
+/// - `Polynomial` with W(x) = A ((x - x_0) / L)^order on absorbing layer of given `width` L.
+/// - `Manolopoulos` transmission-free potential, its width is determined by the minimal energy.
+/// - `RissMeyer` monomial of given `order` on the layer spanning the longest de Broglie wavelength,
+///   for which the reflection from the absorbing layer is negligible.
+///
+/// For polynomial forms the strength A is chosen such that the wave with minimal energy
+/// that travels through the layer and back is damped to `transmission` of its probability.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AbsorbingForm {
+    Polynomial { order: i32, width: f64 },
+    Manolopoulos,
+    RissMeyer { order: i32 },
+}
+
+/// Complex absorbing potential -iW(x) placed on the `boundary` of the grid
+/// that absorbs waves with energy above `min_energy` of particle with mass `mass`.
+/// Serialized parameters describe the absorbing propagator in the [`RunManifest`](crate::manifest::RunManifest),
+/// from which the potential can be rebuilt using [`RunManifest::operation_parameters`](crate::manifest::RunManifest::operation_parameters).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AbsorbingPotential {
+    form: AbsorbingForm,
+    boundary: Boundary,
+    min_energy: f64,
+    mass: f64,
+    transmission: f64,
+}
+
+impl AbsorbingPotential {
+    /// Creates new `AbsorbingPotential` with given form on given boundary absorbing energies above `min_energy`.
+    pub fn new(form: AbsorbingForm, boundary: Boundary, min_energy: f64, mass: f64) -> Self {
+        assert!(min_energy > 0.0, "Minimal energy to absorb has to be positive");
+        assert!(mass > 0.0, "Mass has to be positive");
+
+        AbsorbingPotential {
+            form,
+            boundary,
+            min_energy,
+            mass,
+            transmission: 1e-3,
+        }
+    }
+
+    /// Sets the probability fraction of the wave with minimal energy that survives the absorbing layer.
+    pub fn set_transmission(&mut self, transmission: f64) {
+        assert!(transmission > 0.0 && transmission < 1.0, "Transmission has to be in (0, 1)");
+
+        self.transmission = transmission;
+    }
+
+    /// Returns the minimal wave number that is absorbed.
+    pub fn min_momentum(&self) -> f64 {
+        (2.0 * self.mass * self.min_energy).sqrt()
+    }
+
+    /// Returns the width of the absorbing layer on each boundary.
+    pub fn width(&self) -> f64 {
+        match self.form {
+            AbsorbingForm::Polynomial { width, .. } => width,
+            AbsorbingForm::Manolopoulos => MANOLOPOULOS_C / (2.0 * MANOLOPOULOS_DELTA * self.min_momentum()),
+            AbsorbingForm::RissMeyer { .. } => 2.0 * std::f64::consts::PI / self.min_momentum(),
+        }
+    }
+
+    /// Returns the strength of polynomial forms of the absorbing potential.
+    fn strength(&self, order: i32, width: f64) -> f64 {
+        let velocity = self.min_momentum() / self.mass;
+
+        (order as f64 + 1.0) * velocity * (1.0 / self.transmission).ln() / (4.0 * width)
+    }
+
+    /// Returns the value of W at the distance `depth` from the inner edge of the absorbing layer.
+    /// The Manolopoulos form is capped near the outer edge, so that W stays finite on the whole grid.
+    fn layer_value(&self, depth: f64) -> f64 {
+        let width = self.width();
+
+        match self.form {
+            AbsorbingForm::Polynomial { order, .. } | AbsorbingForm::RissMeyer { order } => {
+                self.strength(order, width) * (depth / width).powi(order)
+            }
+            AbsorbingForm::Manolopoulos => {
+                let c = MANOLOPOULOS_C;
+                let a = 1.0 - 16.0 / c.powi(3);
+                let b = (1.0 - 17.0 / c.powi(3)) / c.powi(2);
+                let x = (c * depth / width).min(c * MANOLOPOULOS_EDGE);
+
+                self.min_energy * (a * x - b * x.powi(3) + 4.0 / (c - x).powi(2) - 4.0 / (c + x).powi(2))
+            }
+        }
+    }
+
+    /// Returns W(x) on given `grid`, so that the absorbing hamiltonian is -iW(x).
+    pub fn potential(&self, grid: &Grid) -> Array1<f64> {
+        let start = *grid.nodes.first().unwrap();
+        let end = *grid.nodes.last().unwrap();
+        let width = self.width();
+        assert!(
+            self.boundary != Boundary::Both || 2.0 * width <= end - start,
+            "Absorbing layers overlap"
+        );
+
+        grid.nodes
+            .iter()
+            .map(|&x| {
+                let depth_end = x - (end - width);
+                let depth_start = (start + width) - x;
+
+                match self.boundary {
+                    Boundary::End if depth_end > 0.0 => self.layer_value(depth_end),
+                    Boundary::Start if depth_start > 0.0 => self.layer_value(depth_start),
+                    Boundary::Both if depth_end > 0.0 => self.layer_value(depth_end),
+                    Boundary::Both if depth_start > 0.0 => self.layer_value(depth_start),
+                    _ => 0.0,
+                }
+            })
+            .collect()
+    }
+}
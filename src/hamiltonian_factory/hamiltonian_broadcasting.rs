@@ -9,7 +9,7 @@ pub fn two_dim_into_n_dim_operator(
     grid1: &Grid,
     grid2: &Grid,
 ) -> ArrayD<f64> {
-    let mut n_dim_operator = Array::zeros(example_wave_function.array.raw_dim());
+    let mut n_dim_operator = Array::zeros(example_wave_function.array().raw_dim());
 
     n_dim_operator.swap_axes(n_dim_operator.ndim() - 2, grid1.dimension_no);
     n_dim_operator.swap_axes(n_dim_operator.ndim() - 1, grid2.dimension_no);
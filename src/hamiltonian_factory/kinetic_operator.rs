@@ -1,11 +1,17 @@
-use ndarray::Array1;
+use ndarray::{Array1, ArrayD, Axis};
 use quantum::particles::Particles;
 use std::f64::consts::PI;
 
-use crate::grid::Grid;
+use crate::{grid::Grid, propagator::fft_transformation::momentum_grid, wave_function::WaveFunction};
 
-/// Creates kinetic Hamiltonian for given grid and collision parameters.
+/// Panics if the `grid` is not uniform, e.g. mapped grids require [`MappedKineticPropagator`](crate::propagator::mapped_kinetic_propagator::MappedKineticPropagator).
+fn assert_uniform(grid: &Grid) {
+    assert!(grid.is_uniform(), "Kinetic hamiltonian applied by FFT requires uniform grid {}", grid.name);
+}
+
+/// Creates kinetic Hamiltonian for given uniform grid and collision parameters.
 pub fn kinetic_hamiltonian(grid: &Grid, collision_params: &Particles) -> Array1<f64> {
+    assert_uniform(grid);
     let momentum_step = 2.0 * PI / (grid.nodes.last().unwrap() - grid.nodes.first().unwrap()) * (1. - 1. / grid.nodes_no as f64);
     let length: i64 = grid.nodes_no as i64;
 
@@ -19,3 +25,49 @@ pub fn kinetic_hamiltonian(grid: &Grid, collision_params: &Particles) -> Array1<
         .map(|k| k * k / (2.0 * collision_params.red_mass()))
         .collect()
 }
+
+/// Creates n dimensional kinetic Hamiltonian in the basis of [`MultiFFTTransformation`](crate::propagator::multi_fft_transformation::MultiFFTTransformation)
+/// as the sum of kinetic energies along given `grids` with corresponding `masses`.
+/// The shape of the operator is taken from `example_wave_function`.
+pub fn n_dim_kinetic_hamiltonian(example_wave_function: &WaveFunction, grids: &[&Grid], masses: &[f64]) -> ArrayD<f64> {
+    assert!(grids.len() == masses.len(), "Number of grids and masses have to be equal");
+
+    let mut n_dim_operator = ArrayD::zeros(example_wave_function.array().raw_dim());
+
+    for (grid, mass) in grids.iter().zip(masses.iter()) {
+        assert_uniform(grid);
+        let momenta = momentum_grid(grid, "momentum").nodes;
+        let energies: Array1<f64> = momenta.iter().map(|k| k * k / (2.0 * mass)).collect();
+
+        n_dim_operator
+            .lanes_mut(Axis(grid.dimension_no))
+            .into_iter()
+            .for_each(|mut lane| lane += &energies);
+    }
+
+    n_dim_operator
+}
+
+/// Creates kinetic Hamiltonian in the basis of [`DSTTransformation`](crate::propagator::dst_transformation::DSTTransformation)
+/// for given grid of interior points between hard walls and collision parameters.
+pub fn sine_kinetic_hamiltonian(grid: &Grid, collision_params: &Particles) -> Array1<f64> {
+    assert_uniform(grid);
+    let step = (grid.nodes.last().unwrap() - grid.nodes.first().unwrap()) / (grid.nodes_no as f64 - 1.0);
+    let momentum_step = PI / (step * (grid.nodes_no as f64 + 1.0));
+
+    (1..=grid.nodes_no)
+        .map(|n| (n as f64 * momentum_step).powi(2) / (2.0 * collision_params.red_mass()))
+        .collect()
+}
+
+/// Creates kinetic Hamiltonian in the basis of [`DCTTransformation`](crate::propagator::dct_transformation::DCTTransformation)
+/// for given grid with walls at its ends and collision parameters.
+pub fn cosine_kinetic_hamiltonian(grid: &Grid, collision_params: &Particles) -> Array1<f64> {
+    assert_uniform(grid);
+    let step = (grid.nodes.last().unwrap() - grid.nodes.first().unwrap()) / (grid.nodes_no as f64 - 1.0);
+    let momentum_step = PI / (step * (grid.nodes_no as f64 - 1.0));
+
+    (0..grid.nodes_no)
+        .map(|n| (n as f64 * momentum_step).powi(2) / (2.0 * collision_params.red_mass()))
+        .collect()
+}
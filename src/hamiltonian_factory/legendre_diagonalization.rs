@@ -9,7 +9,8 @@ use crate::{
     special_functions::legendre_polynomials,
 };
 
-/// Creates diagonalization to Legendre polynomials eigenbasis for given polar_grid
+/// Creates diagonalization to Legendre polynomials eigenbasis for given polar_grid,
+/// it is unitary for the polar grid created by [`Grid::new_gauss_legendre`].
 pub fn legendre_diagonalization_operator(polar_grid: &Grid) -> MatrixTransformation {
     let l_max = polar_grid.nodes_no as i64 - 1;
     let l: Vec<i64> = (0..=l_max).collect();
@@ -28,7 +28,7 @@ impl Control for LeakControl {
         "LeakControl"
     }
 
-    fn first_half(&mut self, wave_function: &mut WaveFunction) {
+    fn first_half(&mut self, wave_function: &mut WaveFunction, _time: f64) {
         self.norm = wave_function.norm();
 
         if let Some(loss_checker) = &mut self.loss_checked {
@@ -36,9 +36,9 @@ impl Control for LeakControl {
         }
     }
 
-    fn second_half(&mut self, wave_function: &mut WaveFunction) {
+    fn second_half(&mut self, wave_function: &mut WaveFunction, time: f64) {
         if let Some(loss_checker) = &mut self.loss_checked {
-            loss_checker.check_after(wave_function);
+            loss_checker.check_after(wave_function, time);
         }
 
         wave_function.normalize(self.norm);
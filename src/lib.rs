@@ -2,14 +2,23 @@ pub mod border_dumping;
 pub mod change_observer;
 pub mod control;
 pub mod grid;
+pub mod hamiltonian;
 pub mod hamiltonian_factory;
 pub mod leak_control;
+pub mod linear_algebra;
 pub mod loss_checker;
+pub mod loss_distribution;
 pub mod loss_saver;
+pub mod manifest;
 pub mod propagation;
+pub mod propagation_handle;
+pub mod profiler;
 pub mod propagator;
+pub mod report;
 pub mod saver;
 pub mod special_functions;
+pub mod stop_condition;
+pub mod sweep;
 pub mod time_grid;
 pub mod wave_function;
 pub mod wave_function_saver;
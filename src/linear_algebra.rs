@@ -0,0 +1,417 @@
+use ndarray::{Array1, Array2};
+use num::complex::Complex64;
+
+/// Returns eigenvalues in ascending order and eigenvectors as columns of real symmetric `matrix`.
+/// Uses Householder tridiagonalization followed by implicit QL iterations.
+pub fn symmetric_eigen(matrix: &Array2<f64>) -> (Array1<f64>, Array2<f64>) {
+    let n = matrix.nrows();
+    assert!(matrix.ncols() == n, "Matrix has to be square");
+
+    let mut v = matrix.clone();
+    let mut d = Array1::<f64>::zeros(n);
+    let mut e = Array1::<f64>::zeros(n);
+    if n == 0 {
+        return (d, v);
+    }
+
+    tridiagonalize(&mut v, &mut d, &mut e);
+    tridiagonal_ql(&mut v, &mut d, &mut e);
+
+    for i in 0..n - 1 {
+        let mut k = i;
+        let mut p = d[i];
+        for j in i + 1..n {
+            if d[j] < p {
+                k = j;
+                p = d[j];
+            }
+        }
+        if k != i {
+            d[k] = d[i];
+            d[i] = p;
+            for j in 0..n {
+                v.swap([j, i], [j, k]);
+            }
+        }
+    }
+
+    (d, v)
+}
+
+/// Returns eigenvalues in ascending order of symmetric tridiagonal matrix with given `diagonal`
+/// and `off_diagonal`, where `off_diagonal[i]` couples elements `i` and `i + 1`.
+pub fn tridiagonal_eigenvalues(diagonal: &[f64], off_diagonal: &[f64]) -> Vec<f64> {
+    let n = diagonal.len();
+    assert!(off_diagonal.len() + 1 == n, "Off diagonal has to be shorter by one than the diagonal");
+
+    let mut v = Array2::<f64>::eye(n);
+    let mut d = Array1::from_vec(diagonal.to_vec());
+    let mut e = Array1::<f64>::zeros(n);
+    for i in 1..n {
+        e[i] = off_diagonal[i - 1];
+    }
+    tridiagonal_ql(&mut v, &mut d, &mut e);
+
+    let mut eigenvalues = d.to_vec();
+    eigenvalues.sort_by(|a, b| a.total_cmp(b));
+
+    eigenvalues
+}
+
+/// Householder reduction of symmetric matrix `v` to tridiagonal form with diagonal `d` and subdiagonal `e`,
+/// `v` is replaced by the accumulated orthogonal transformation.
+fn tridiagonalize(v: &mut Array2<f64>, d: &mut Array1<f64>, e: &mut Array1<f64>) {
+    let n = d.len();
+
+    for j in 0..n {
+        d[j] = v[[n - 1, j]];
+    }
+
+    for i in (1..n).rev() {
+        let mut scale = 0.0;
+        let mut h = 0.0;
+        for k in 0..i {
+            scale += d[k].abs();
+        }
+
+        if scale == 0.0 {
+            e[i] = d[i - 1];
+            for j in 0..i {
+                d[j] = v[[i - 1, j]];
+                v[[i, j]] = 0.0;
+                v[[j, i]] = 0.0;
+            }
+        } else {
+            for k in 0..i {
+                d[k] /= scale;
+                h += d[k] * d[k];
+            }
+            let mut f = d[i - 1];
+            let mut g = if f > 0.0 { -h.sqrt() } else { h.sqrt() };
+            e[i] = scale * g;
+            h -= f * g;
+            d[i - 1] = f - g;
+            for j in 0..i {
+                e[j] = 0.0;
+            }
+
+            for j in 0..i {
+                f = d[j];
+                v[[j, i]] = f;
+                g = e[j] + v[[j, j]] * f;
+                for k in j + 1..i {
+                    g += v[[k, j]] * d[k];
+                    e[k] += v[[k, j]] * f;
+                }
+                e[j] = g;
+            }
+
+            f = 0.0;
+            for j in 0..i {
+                e[j] /= h;
+                f += e[j] * d[j];
+            }
+            let hh = f / (h + h);
+            for j in 0..i {
+                e[j] -= hh * d[j];
+            }
+            for j in 0..i {
+                f = d[j];
+                g = e[j];
+                for k in j..i {
+                    v[[k, j]] -= f * e[k] + g * d[k];
+                }
+                d[j] = v[[i - 1, j]];
+                v[[i, j]] = 0.0;
+            }
+        }
+        d[i] = h;
+    }
+
+    for i in 0..n - 1 {
+        v[[n - 1, i]] = v[[i, i]];
+        v[[i, i]] = 1.0;
+        let h = d[i + 1];
+        if h != 0.0 {
+            for k in 0..=i {
+                d[k] = v[[k, i + 1]] / h;
+            }
+            for j in 0..=i {
+                let mut g = 0.0;
+                for k in 0..=i {
+                    g += v[[k, i + 1]] * v[[k, j]];
+                }
+                for k in 0..=i {
+                    v[[k, j]] -= g * d[k];
+                }
+            }
+        }
+        for k in 0..=i {
+            v[[k, i + 1]] = 0.0;
+        }
+    }
+
+    for j in 0..n {
+        d[j] = v[[n - 1, j]];
+        v[[n - 1, j]] = 0.0;
+    }
+    v[[n - 1, n - 1]] = 1.0;
+    e[0] = 0.0;
+}
+
+/// Implicit QL iterations on tridiagonal matrix with diagonal `d` and subdiagonal `e`,
+/// accumulating eigenvectors into `v`.
+fn tridiagonal_ql(v: &mut Array2<f64>, d: &mut Array1<f64>, e: &mut Array1<f64>) {
+    let n = d.len();
+
+    for i in 1..n {
+        e[i - 1] = e[i];
+    }
+    e[n - 1] = 0.0;
+
+    let mut f = 0.0;
+    let mut tst1: f64 = 0.0;
+    let eps = f64::EPSILON;
+
+    for l in 0..n {
+        tst1 = tst1.max(d[l].abs() + e[l].abs());
+        let mut m = l;
+        while m < n - 1 {
+            if e[m].abs() <= eps * tst1 {
+                break;
+            }
+            m += 1;
+        }
+
+        if m > l {
+            loop {
+                let mut g = d[l];
+                let mut p = (d[l + 1] - g) / (2.0 * e[l]);
+                let mut r = p.hypot(1.0);
+                if p < 0.0 {
+                    r = -r;
+                }
+                d[l] = e[l] / (p + r);
+                d[l + 1] = e[l] * (p + r);
+                let dl1 = d[l + 1];
+                let mut h = g - d[l];
+                for i in l + 2..n {
+                    d[i] -= h;
+                }
+                f += h;
+
+                p = d[m];
+                let mut c = 1.0;
+                let mut c2 = c;
+                let mut c3 = c;
+                let el1 = e[l + 1];
+                let mut s = 0.0;
+                let mut s2 = 0.0;
+                for i in (l..m).rev() {
+                    c3 = c2;
+                    c2 = c;
+                    s2 = s;
+                    g = c * e[i];
+                    h = c * p;
+                    r = p.hypot(e[i]);
+                    e[i + 1] = s * r;
+                    s = e[i] / r;
+                    c = p / r;
+                    p = c * d[i] - s * g;
+                    d[i + 1] = h + s * (c * g + s * d[i]);
+
+                    for k in 0..n {
+                        h = v[[k, i + 1]];
+                        v[[k, i + 1]] = s * v[[k, i]] + c * h;
+                        v[[k, i]] = c * v[[k, i]] - s * h;
+                    }
+                }
+                p = -s * s2 * c3 * el1 * e[l] / dl1;
+                e[l] = s * p;
+                d[l] = c * p;
+
+                if e[l].abs() <= eps * tst1 {
+                    break;
+                }
+            }
+        }
+        d[l] += f;
+        e[l] = 0.0;
+    }
+}
+
+/// Returns eigenvalues in ascending order and eigenvectors as columns of Hermitian `matrix`.
+/// Uses cyclic Jacobi rotations, suitable for small matrices such as channel couplings.
+pub fn hermitian_eigen(matrix: &Array2<Complex64>) -> (Array1<f64>, Array2<Complex64>) {
+    let n = matrix.nrows();
+    assert!(matrix.ncols() == n, "Matrix has to be square");
+
+    let mut a = matrix.clone();
+    let mut v = Array2::<Complex64>::eye(n);
+
+    let total_norm: f64 = a.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+    let tolerance = f64::EPSILON * total_norm.max(f64::MIN_POSITIVE);
+
+    for _ in 0..100 {
+        let mut off_diagonal = 0.0;
+        for p in 0..n {
+            for q in p + 1..n {
+                off_diagonal += a[[p, q]].norm_sqr();
+            }
+        }
+        if off_diagonal.sqrt() <= tolerance {
+            break;
+        }
+
+        for p in 0..n {
+            for q in p + 1..n {
+                let g = a[[p, q]].norm();
+                if g <= tolerance / n as f64 {
+                    continue;
+                }
+                let phase = a[[p, q]] / g;
+
+                let tau = (a[[q, q]].re - a[[p, p]].re) / (2.0 * g);
+                let t = tau.signum() / (tau.abs() + (1.0 + tau * tau).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = t * c;
+
+                // Rotation acting on columns p and q
+                let r_pp = Complex64::from(c);
+                let r_pq = Complex64::from(s);
+                let r_qp = -s * phase.conj();
+                let r_qq = c * phase.conj();
+
+                for k in 0..n {
+                    let a_kp = a[[k, p]];
+                    let a_kq = a[[k, q]];
+                    a[[k, p]] = a_kp * r_pp + a_kq * r_qp;
+                    a[[k, q]] = a_kp * r_pq + a_kq * r_qq;
+
+                    let v_kp = v[[k, p]];
+                    let v_kq = v[[k, q]];
+                    v[[k, p]] = v_kp * r_pp + v_kq * r_qp;
+                    v[[k, q]] = v_kp * r_pq + v_kq * r_qq;
+                }
+                for k in 0..n {
+                    let a_pk = a[[p, k]];
+                    let a_qk = a[[q, k]];
+                    a[[p, k]] = r_pp.conj() * a_pk + r_qp.conj() * a_qk;
+                    a[[q, k]] = r_pq.conj() * a_pk + r_qq.conj() * a_qk;
+                }
+
+                a[[p, q]] = Complex64::from(0.0);
+                a[[q, p]] = Complex64::from(0.0);
+                a[[p, p]] = Complex64::from(a[[p, p]].re);
+                a[[q, q]] = Complex64::from(a[[q, q]].re);
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| a[[i, i]].re.total_cmp(&a[[j, j]].re));
+
+    let eigenvalues = order.iter().map(|&i| a[[i, i]].re).collect();
+    let mut eigenvectors = Array2::<Complex64>::zeros((n, n));
+    for (new, &old) in order.iter().enumerate() {
+        eigenvectors.column_mut(new).assign(&v.column(old));
+    }
+
+    (eigenvalues, eigenvectors)
+}
+
+/// Returns exp(`factor` H) of Hermitian matrix H using its eigendecomposition,
+/// e.g. `factor` = -i dt gives the propagator of H for complex time step dt.
+pub fn hermitian_exponential(matrix: &Array2<Complex64>, factor: Complex64) -> Array2<Complex64> {
+    let (eigenvalues, eigenvectors) = hermitian_eigen(matrix);
+
+    let mut scaled = eigenvectors.clone();
+    for (mut column, value) in scaled.columns_mut().into_iter().zip(eigenvalues.iter()) {
+        column *= (factor * value).exp();
+    }
+
+    scaled.dot(&eigenvectors.t().mapv(|x| x.conj()))
+}
+
+/// Returns exponential of general complex `matrix` using diagonal Padé approximant of order 6 with scaling and squaring.
+pub fn matrix_exponential(matrix: &Array2<Complex64>) -> Array2<Complex64> {
+    const ORDER: usize = 6;
+    let n = matrix.nrows();
+    assert!(matrix.ncols() == n, "Matrix has to be square");
+
+    let norm = matrix
+        .columns()
+        .into_iter()
+        .map(|column| column.iter().map(|x| x.norm()).sum::<f64>())
+        .fold(0.0, f64::max);
+    let squarings = if norm > 0.5 { (norm / 0.5).log2().ceil() as i32 } else { 0 };
+    let a = matrix / Complex64::from(2f64.powi(squarings));
+
+    let mut numerator = Array2::<Complex64>::eye(n);
+    let mut denominator = Array2::<Complex64>::eye(n);
+    let mut power = Array2::<Complex64>::eye(n);
+    let mut coefficient = 1.0;
+    for k in 1..=ORDER {
+        coefficient *= (ORDER - k + 1) as f64 / (k * (2 * ORDER - k + 1)) as f64;
+        power = power.dot(&a);
+
+        let term = &power * Complex64::from(coefficient);
+        numerator += &term;
+        if k % 2 == 0 {
+            denominator += &term;
+        } else {
+            denominator -= &term;
+        }
+    }
+
+    let mut exponential = solve(denominator, numerator);
+    for _ in 0..squarings {
+        exponential = exponential.dot(&exponential);
+    }
+
+    exponential
+}
+
+/// Solves linear system A X = B using Gaussian elimination with partial pivoting.
+fn solve(mut a: Array2<Complex64>, mut b: Array2<Complex64>) -> Array2<Complex64> {
+    let n = a.nrows();
+
+    for i in 0..n {
+        let pivot = (i..n)
+            .max_by(|&j, &k| a[[j, i]].norm().total_cmp(&a[[k, i]].norm()))
+            .unwrap();
+        assert!(a[[pivot, i]].norm() > 0.0, "Matrix is singular");
+        if pivot != i {
+            for k in 0..n {
+                a.swap([i, k], [pivot, k]);
+            }
+            for k in 0..b.ncols() {
+                b.swap([i, k], [pivot, k]);
+            }
+        }
+
+        for j in i + 1..n {
+            let factor = a[[j, i]] / a[[i, i]];
+            for k in i..n {
+                let value = a[[i, k]];
+                a[[j, k]] -= factor * value;
+            }
+            for k in 0..b.ncols() {
+                let value = b[[i, k]];
+                b[[j, k]] -= factor * value;
+            }
+        }
+    }
+
+    for i in (0..n).rev() {
+        for k in 0..b.ncols() {
+            let mut value = b[[i, k]];
+            for j in i + 1..n {
+                value -= a[[i, j]] * b[[j, k]];
+            }
+            b[[i, k]] = value / a[[i, i]];
+        }
+    }
+
+    b
+}
@@ -1,14 +1,16 @@
-use crate::{loss_saver::LossSaver, time_grid::TimeGrid, wave_function::WaveFunction};
+use crate::{loss_distribution::LossDistribution, loss_saver::LossSaver, time_grid::TimeGrid, wave_function::WaveFunction};
 
 /// Checks the loss of norm of the wave function.
 /// `LossChecker` is used to check loss of norm of the wave function during the use of `Propagator` on wave function if needed.
-/// It also stores the cumulative loss of norm during checks.
+/// It also stores the cumulative loss of norm during checks
+/// and optionally its distribution on the grids using [`LossDistribution`].
 #[derive(Clone)]
 pub struct LossChecker {
     pub name: String,
     loss: f64,
     current_norm: f64,
     loss_saver: Option<LossSaver>,
+    distribution: Option<LossDistribution>,
 }
 
 impl LossChecker {
@@ -19,6 +21,7 @@ impl LossChecker {
             loss: 0.0,
             current_norm: 1.0,
             loss_saver: None,
+            distribution: None,
         }
     }
 
@@ -33,9 +36,30 @@ impl LossChecker {
             loss: 0.0,
             current_norm: 1.0,
             loss_saver: Some(LossSaver::new(filename, frames_no, time_grid)),
+            distribution: None,
         }
     }
 
+    /// Enables resolving the lost norm on the grids and along given `axes` monitored `frames_no` times during propagation.
+    pub fn add_distribution(&mut self, filename: String, axes: Vec<usize>, frames_no: usize, time_grid: &TimeGrid) {
+        self.distribution = Some(LossDistribution::new(filename, axes, frames_no, time_grid));
+    }
+
+    /// Sets the [`LossSaver`] monitoring the cumulative loss, e.g. created with `LossSaver::with_duration`.
+    pub fn set_loss_saver(&mut self, loss_saver: LossSaver) {
+        self.loss_saver = Some(loss_saver);
+    }
+
+    /// Sets the [`LossDistribution`] resolving the lost norm, e.g. created with `LossDistribution::with_duration`.
+    pub fn set_distribution(&mut self, distribution: LossDistribution) {
+        self.distribution = Some(distribution);
+    }
+
+    /// Returns the distribution of the lost norm if enabled.
+    pub fn distribution(&self) -> &Option<LossDistribution> {
+        &self.distribution
+    }
+
     /// Return the cumulative loss of norm from checks.
     pub fn loss(&self) -> f64 {
         if let Some(loss_saver) = &self.loss_saver {
@@ -45,18 +69,31 @@ impl LossChecker {
         self.loss
     }
 
+    /// Return the cumulative loss of norm from checks without saving monitored losses.
+    pub fn cumulative_loss(&self) -> f64 {
+        self.loss
+    }
+
     /// Check the norm of the wave function before possible norm change.
     pub fn check_before(&mut self, wave_function: &mut WaveFunction) {
         self.current_norm = wave_function.norm();
+
+        if let Some(distribution) = &mut self.distribution {
+            distribution.check_before(wave_function);
+        }
     }
 
-    /// Check the norm of the wave function after possible norm change.
-    pub fn check_after(&mut self, wave_function: &mut WaveFunction) {
+    /// Check the norm of the wave function after possible norm change at given `time`.
+    pub fn check_after(&mut self, wave_function: &mut WaveFunction, time: f64) {
         let new_norm = wave_function.norm();
         self.loss += self.current_norm - new_norm;
 
         if let Some(loss_saver) = &mut self.loss_saver {
-            loss_saver.monitor(self.loss);
+            loss_saver.monitor(self.loss, time);
+        }
+
+        if let Some(distribution) = &mut self.distribution {
+            distribution.check_after(wave_function, time);
         }
 
         self.current_norm = new_norm;
@@ -65,5 +102,9 @@ impl LossChecker {
     /// Reset the cumulative loss of norm.
     pub fn reset(&mut self) {
         self.loss = 0.0;
+
+        if let Some(distribution) = &mut self.distribution {
+            distribution.reset();
+        }
     }
 }
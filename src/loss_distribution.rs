@@ -0,0 +1,137 @@
+use ndarray::{s, Array, Array1, Array2, ArrayD, Axis};
+use ndarray_npy::write_npy;
+
+use crate::{saver::FrameSampler, time_grid::TimeGrid, wave_function::WaveFunction};
+
+/// Distribution of the norm lost by the wave function on actual grids.
+/// Used inside [`LossChecker`](crate::loss_checker::LossChecker) to resolve the absorbed probability
+/// by region and by quantum numbers of chosen `axes`, e.g. per `l` or Ω index.
+/// It also monitors the absorbed probability along each of the chosen `axes` in time at frames chosen by [`FrameSampler`].
+#[derive(Clone)]
+pub struct LossDistribution {
+    pub name: String,
+    axes: Vec<usize>,
+    density_before: ArrayD<f64>,
+    absorbed: ArrayD<f64>,
+    absorbed_in_time: Vec<Vec<Array1<f64>>>,
+    sampler: FrameSampler,
+    times: Vec<f64>,
+}
+
+impl LossDistribution {
+    /// Creates new `LossDistribution` resolving the loss along `axes` monitored `frames_no` times during propagation.
+    pub fn new(name: String, axes: Vec<usize>, frames_no: usize, time_grid: &TimeGrid) -> Self {
+        Self::with_duration(name, axes, frames_no, time_grid.duration())
+    }
+
+    /// Creates new `LossDistribution` with frames spread over the propagation lasting `duration`,
+    /// e.g. the duration of a [`TimeSchedule`](crate::time_grid::TimeSchedule).
+    pub fn with_duration(name: String, axes: Vec<usize>, frames_no: usize, duration: f64) -> Self {
+        LossDistribution {
+            name,
+            absorbed_in_time: vec![Vec::with_capacity(frames_no); axes.len()],
+            axes,
+            density_before: ArrayD::zeros(vec![0]),
+            absorbed: ArrayD::zeros(vec![0]),
+            sampler: FrameSampler::new(duration, frames_no),
+            times: Vec::with_capacity(frames_no),
+        }
+    }
+
+    /// Observes the density of the wave function before possible norm change.
+    pub fn check_before(&mut self, wave_function: &mut WaveFunction) {
+        self.density_before = wave_function.weighted_density();
+    }
+
+    /// Accumulates the density lost since the `check_before` and monitors the absorbed distribution at given `time`.
+    pub fn check_after(&mut self, wave_function: &mut WaveFunction, time: f64) {
+        let density_after = wave_function.weighted_density();
+
+        if self.absorbed.shape() != density_after.shape() {
+            self.absorbed = Array::zeros(density_after.raw_dim());
+        }
+        self.absorbed += &(&self.density_before - &density_after);
+
+        if self.sampler.sample(time).is_some() {
+            for (i, &axis) in self.axes.iter().enumerate() {
+                let projection = self.projection(axis);
+                self.absorbed_in_time[i].push(projection);
+            }
+            self.times.push(time);
+        }
+    }
+
+    /// Returns cumulative absorbed density on the grids at which the loss was checked.
+    pub fn absorbed(&self) -> &ArrayD<f64> {
+        &self.absorbed
+    }
+
+    /// Returns cumulative absorbed probability resolved along given `axis`.
+    pub fn projection(&self, axis: usize) -> Array1<f64> {
+        if self.absorbed.ndim() == 1 {
+            return self.absorbed.clone().into_dimensionality().unwrap();
+        }
+
+        self.absorbed
+            .axis_iter(Axis(axis))
+            .map(|sub_array| sub_array.sum())
+            .collect()
+    }
+
+    /// Returns absorbed probability resolved along given `axis` for each monitored time, with times along the first axis.
+    pub fn projection_in_time(&self, axis: usize) -> Option<Array2<f64>> {
+        let index = self.axes.iter().position(|&x| x == axis)?;
+        let frames = &self.absorbed_in_time[index];
+        let nodes_no = frames.first().map_or(0, |x| x.len());
+
+        let mut projections = Array2::zeros((frames.len(), nodes_no));
+        for (i, frame) in frames.iter().enumerate() {
+            projections.slice_mut(s![i, ..]).assign(frame);
+        }
+
+        Some(projections)
+    }
+
+    /// Returns times at which the absorbed distribution was monitored.
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Saves the absorbed density and its projections in time along chosen axes.
+    pub fn save(&self) -> Result<(), &str> {
+        let path = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let result = write_npy(&format!("{path}/{}_absorbed.npy", self.name), &self.absorbed);
+        if result.is_err() {
+            return Err("Failed to save absorbed density");
+        }
+
+        for &axis in &self.axes {
+            let projections = self.projection_in_time(axis).unwrap();
+            let result = write_npy(&format!("{path}/{}_absorbed_axis_{axis}.npy", self.name), &projections);
+            if result.is_err() {
+                return Err("Failed to save absorbed projection");
+            }
+        }
+
+        let times: Array1<f64> = Array::from_vec(self.times.clone());
+        let result = write_npy(&format!("{path}/{}_absorbed_time.npy", self.name), &times);
+        if result.is_err() {
+            return Err("Failed to save time grid")
+        }
+
+        Ok(())
+    }
+
+    /// Resets the absorbed density and monitored projections.
+    pub fn reset(&mut self) {
+        self.absorbed = ArrayD::zeros(vec![0]);
+        self.absorbed_in_time.iter_mut().for_each(|x| x.clear());
+        self.times.clear();
+        self.sampler.reset();
+    }
+}
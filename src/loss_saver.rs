@@ -1,41 +1,37 @@
 use std::{fs::File, io::Write};
 
-use crate::time_grid::TimeGrid;
+use crate::{saver::FrameSampler, time_grid::TimeGrid};
 
 #[derive(Clone)]
 pub struct LossSaver {
     pub name: String,
     losses: Vec<f64>,
-    current_frame: usize,
-    frames_no: usize,
-    total_frames: usize,
-    step: f64,
+    sampler: FrameSampler,
     times: Vec<f64>,
 }
 
 impl LossSaver {
     pub fn new(name: String, frames_no: usize, time_grid: &TimeGrid) -> LossSaver {
+        LossSaver::with_duration(name, frames_no, time_grid.duration())
+    }
+
+    /// Creates new `LossSaver` with frames spread over the propagation lasting `duration`,
+    /// e.g. the duration of a [`TimeSchedule`](crate::time_grid::TimeSchedule).
+    pub fn with_duration(name: String, frames_no: usize, duration: f64) -> LossSaver {
         LossSaver {
             name,
             losses: Vec::with_capacity(frames_no),
-            current_frame: 0,
-            frames_no,
-            total_frames: time_grid.step_no,
-            step: time_grid.step,
+            sampler: FrameSampler::new(duration, frames_no),
             times: Vec::with_capacity(frames_no),
         }
     }
 
-    pub fn monitor(&mut self, loss: f64) {
-        // It is monitored two times during the propagation step.
-        let frequency = 2 * self.total_frames / self.frames_no;
-
-        if self.current_frame % frequency == 0 && self.current_frame / frequency < self.frames_no {
+    /// Monitors cumulative `loss` at given `time`.
+    pub fn monitor(&mut self, loss: f64, time: f64) {
+        if self.sampler.sample(time).is_some() {
             self.losses.push(loss);
-            self.times.push((self.current_frame as f64 + 1.0) / 2. * self.step);
+            self.times.push(time);
         }
-
-        self.current_frame += 1;
     }
 
     pub fn save(&self) {
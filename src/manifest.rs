@@ -0,0 +1,183 @@
+use std::{collections::BTreeMap, fs::File, io::Write};
+
+use ndarray::{Array, ArrayD, Dimension};
+use ndarray_npy::read_npy;
+use num::complex::Complex64;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    grid::Grid,
+    profiler::OperationProfile,
+    report::{OperationKind, PropagationReport},
+    time_grid::TimeGrid,
+};
+
+/// Description of a single operation in the operation stack.
+/// - `name` is the user supplied name of the operation or its type name if not supplied.
+/// - `type_name` is the name returned by the operation itself.
+/// - `apply` is the half of the step at which savers and controls are applied.
+/// - `order` is the order of the transformation.
+/// - `description` is what the propagator or transformation acts on.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OperationManifest {
+    pub name: String,
+    pub type_name: String,
+    pub kind: OperationKind,
+    pub apply: Option<String>,
+    pub order: Option<String>,
+    #[serde(default)]
+    pub description: OperationDescription,
+}
+
+/// Description of the operation provided by propagators and transformations.
+/// - `step` is the time step of the propagator, `Full` or `Half`.
+/// - `dimensions` are the dimension numbers of the wave function the operation acts along, empty if it acts on all of them.
+/// - `grids` are the names of the grids the transformation swaps into the wave function in the first half of the step.
+/// - `generator` is the kind of the hamiltonian generating the propagator, `None` if the operator was set directly.
+/// - `hamiltonian_hash` is the [`array_hash`] of the generating hamiltonian, used to check it against the saved arrays.
+/// - `parameters` are the parameters of the operator, e.g. the mass of the kinetic term or the absorbing potential form,
+///   set by [`OperationStack::describe_last_operation`](crate::propagation::OperationStack::describe_last_operation).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OperationDescription {
+    pub step: Option<String>,
+    pub dimensions: Vec<usize>,
+    pub grids: Vec<String>,
+    pub generator: Option<String>,
+    #[serde(default)]
+    pub hamiltonian_hash: Option<String>,
+    #[serde(default)]
+    pub parameters: BTreeMap<String, serde_json::Value>,
+}
+
+/// Returns the hexadecimal FNV-1a hash of the shape and values of the `arrays`,
+/// that is stable between runs and platforms.
+pub fn array_hash<'a, D: Dimension + 'a>(arrays: impl IntoIterator<Item = &'a Array<Complex64, D>>) -> String {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET;
+    let mut feed = |value: u64| {
+        for byte in value.to_le_bytes() {
+            hash = (hash ^ byte as u64).wrapping_mul(PRIME);
+        }
+    };
+
+    for array in arrays {
+        for &size in array.shape() {
+            feed(size as u64);
+        }
+        for value in array.iter() {
+            feed(value.re.to_bits());
+            feed(value.im.to_bits());
+        }
+    }
+
+    format!("{hash:016x}")
+}
+
+/// Machine readable description of the propagation run written next to its outputs,
+/// created by [`Propagation::manifest`](crate::propagation::Propagation::manifest).
+/// - `version` is the version of the crate that produced the run.
+/// - `grids` are the grids of the wave function.
+/// - `time_schedule` are the uniform segments of the time grid.
+/// - `time` is the time at the end of the run.
+/// - `operations` are the operations in the stack order.
+/// - `report` contains the losses, final norm and wall times of the run.
+/// - `profiles` are the wall times of each operation if profiling was enabled.
+/// - `outputs` are the names of the npy files saved by the savers, without extension.
+/// - `metadata` are user supplied parameters of the run, e.g. particles or potential parameters.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RunManifest {
+    pub name: String,
+    pub version: String,
+    pub grids: Vec<Grid>,
+    pub time_schedule: Vec<TimeGrid>,
+    pub time: f64,
+    pub operations: Vec<OperationManifest>,
+    pub report: PropagationReport,
+    pub profiles: Option<Vec<OperationProfile>>,
+    pub outputs: Vec<String>,
+    pub metadata: BTreeMap<String, serde_json::Value>,
+}
+
+impl RunManifest {
+    /// Adds user supplied `value` under given `key` to the metadata.
+    pub fn insert_metadata<T: Serialize>(&mut self, key: &str, value: T) {
+        let value = serde_json::to_value(value).expect("Metadata has to be serializable to JSON");
+
+        self.metadata.insert(key.to_string(), value);
+    }
+
+    /// Returns the manifest serialized to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Returns the manifest deserialized from JSON.
+    pub fn from_json(json: &str) -> Result<Self, &'static str> {
+        serde_json::from_str(json).map_err(|_| "Failed to parse manifest")
+    }
+
+    /// Saves the manifest as `{name}_manifest.json` file in current directory.
+    pub fn save(&self) -> Result<(), &str> {
+        let path = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let file = File::create(format!("{path}/{}_manifest.json", self.name));
+        let Ok(mut file) = file else {
+            return Err("Failed to create manifest file");
+        };
+
+        if file.write_all(self.to_json().as_bytes()).is_err() {
+            return Err("Failed to save manifest");
+        }
+
+        Ok(())
+    }
+
+    /// Loads the manifest of the run with given `name` from the `directory`.
+    pub fn load(directory: &str, name: &str) -> Result<Self, &'static str> {
+        let json = std::fs::read_to_string(format!("{directory}/{name}_manifest.json"));
+        let Ok(json) = json else {
+            return Err("Failed to read manifest file");
+        };
+
+        Self::from_json(&json)
+    }
+
+    /// Returns the manifest of the operation with given `name`.
+    pub fn operation(&self, name: &str) -> Option<&OperationManifest> {
+        self.operations.iter().find(|operation| operation.name == name)
+    }
+
+    /// Returns the parameters of the operation with given `name` deserialized into `T`,
+    /// e.g. [`AbsorbingPotential`](crate::hamiltonian_factory::absorbing_potentials::AbsorbingPotential) it was created from.
+    pub fn operation_parameters<T: DeserializeOwned>(&self, name: &str) -> Result<T, &'static str> {
+        let Some(operation) = self.operation(name) else {
+            return Err("Operation is not listed in the manifest");
+        };
+        let parameters = serde_json::Value::Object(operation.description.parameters.clone().into_iter().collect());
+
+        serde_json::from_value(parameters).map_err(|_| "Failed to parse operation parameters")
+    }
+
+    /// Reads the saved `output` of the run from the `directory`.
+    pub fn read_output(&self, directory: &str, output: &str) -> Result<ArrayD<f64>, &'static str> {
+        if !self.outputs.iter().any(|x| x == output) {
+            return Err("Output is not listed in the manifest");
+        }
+
+        read_npy(format!("{directory}/{output}.npy")).map_err(|_| "Failed to read output")
+    }
+
+    /// Reads all saved outputs of the run from the `directory`.
+    pub fn read_outputs(&self, directory: &str) -> Result<Vec<(String, ArrayD<f64>)>, &'static str> {
+        self.outputs
+            .iter()
+            .map(|output| Ok((output.clone(), self.read_output(directory, output)?)))
+            .collect()
+    }
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::report::OperationKind;
+
+/// Cumulative wall time and number of calls of a single operation in the operation stack.
+/// - `name` is the user supplied name of the operation or its type name if not supplied.
+/// - `type_name` is the name returned by the operation itself.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OperationProfile {
+    pub name: String,
+    pub type_name: String,
+    pub kind: OperationKind,
+    pub calls: usize,
+    pub elapsed: f64,
+}
+
+/// Records wall time and call counts of each operation in the operation stack.
+#[derive(Clone, Debug, Default)]
+pub struct Profiler {
+    calls: Vec<usize>,
+    elapsed: Vec<Duration>,
+}
+
+impl Profiler {
+    /// Creates new `Profiler` for `operations_len` operations.
+    pub fn new(operations_len: usize) -> Self {
+        Profiler {
+            calls: vec![0; operations_len],
+            elapsed: vec![Duration::ZERO; operations_len],
+        }
+    }
+
+    /// Records single call of operation with index `operation` that lasted `duration`.
+    pub fn record(&mut self, operation: usize, duration: Duration) {
+        if operation >= self.calls.len() {
+            self.calls.resize(operation + 1, 0);
+            self.elapsed.resize(operation + 1, Duration::ZERO);
+        }
+
+        self.calls[operation] += 1;
+        self.elapsed[operation] += duration;
+    }
+
+    /// Returns number of calls of operation with index `operation`.
+    pub fn calls(&self, operation: usize) -> usize {
+        self.calls.get(operation).copied().unwrap_or(0)
+    }
+
+    /// Returns cumulative wall time of operation with index `operation`.
+    pub fn elapsed(&self, operation: usize) -> Duration {
+        self.elapsed.get(operation).copied().unwrap_or(Duration::ZERO)
+    }
+
+    /// Resets all recorded times and calls.
+    pub fn reset(&mut self) {
+        self.calls.iter_mut().for_each(|x| *x = 0);
+        self.elapsed.iter_mut().for_each(|x| *x = Duration::ZERO);
+    }
+}
+
+/// Returns `profiles` formatted as a table sorted by the operation order with the share of the total time.
+pub fn profile_table(profiles: &[OperationProfile]) -> String {
+    let total: f64 = profiles.iter().map(|x| x.elapsed).sum();
+
+    let mut table = format!(
+        "{:<5}{:<16}{:<28}{:<28}{:>10}{:>14}{:>14}{:>9}\n",
+        "no", "kind", "name", "type", "calls", "total [s]", "per call [s]", "share"
+    );
+    for (i, profile) in profiles.iter().enumerate() {
+        let per_call = if profile.calls > 0 { profile.elapsed / profile.calls as f64 } else { 0.0 };
+        let share = if total > 0.0 { 100.0 * profile.elapsed / total } else { 0.0 };
+
+        table.push_str(&format!(
+            "{:<5}{:<16}{:<28}{:<28}{:>10}{:>14.6}{:>14.3e}{:>8.2}%\n",
+            i,
+            format!("{:?}", profile.kind),
+            profile.name,
+            profile.type_name,
+            profile.calls,
+            profile.elapsed,
+            per_call,
+            share
+        ));
+    }
+
+    table
+}
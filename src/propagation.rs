@@ -1,11 +1,25 @@
-use std::sync::Mutex;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use ndarray::ArrayD;
+use serde::Serialize;
 
 use crate::{
     control::{Apply, Control},
+    hamiltonian::{EnergyStatistics, Hamiltonian},
+    manifest::{OperationDescription, OperationManifest, RunManifest},
+    profiler::{profile_table, OperationProfile, Profiler},
+    propagation_handle::{HandleCondition, PropagationHandle, SharedState},
     propagator::{transformation::{Transformation, Order}, Propagator},
+    report::{ElapsedTimes, LossReport, OperationKind, OperationReport, PropagationReport, Reporter, StopReport},
     saver::Saver,
-    time_grid::TimeGrid,
+    stop_condition::StopCondition,
+    time_grid::{TimeGrid, TimeSchedule},
     wave_function::WaveFunction,
+    loss_checker::LossChecker,
 };
 
 /// Enum of all operations that can be performed during step in propagation.
@@ -16,21 +30,174 @@ enum Operations {
     Control(Mutex<Box<dyn Control + Send>>, Apply),
 }
 
+impl Operations {
+    fn kind(&self) -> OperationKind {
+        match self {
+            Operations::Propagator(_) => OperationKind::Propagator,
+            Operations::Transformation(_, _) => OperationKind::Transformation,
+            Operations::Saver(_, _) => OperationKind::Saver,
+            Operations::Control(_, _) => OperationKind::Control,
+        }
+    }
+
+    fn type_name(&self) -> String {
+        match self {
+            Operations::Propagator(propagator) => propagator.lock().unwrap().name().to_string(),
+            Operations::Transformation(transformation, _) => transformation.lock().unwrap().name().to_string(),
+            Operations::Saver(saver, _) => saver.lock().unwrap().name().to_string(),
+            Operations::Control(control, _) => control.lock().unwrap().name().to_string(),
+        }
+    }
+
+    fn report(&self, label: &Option<String>) -> OperationReport {
+        let (name, loss, region_losses) = match self {
+            Operations::Propagator(propagator) => {
+                let borrowed = propagator.lock().unwrap();
+                (borrowed.name().to_string(), loss_report(borrowed.loss()), Vec::new())
+            }
+            Operations::Transformation(transformation, _) => {
+                (transformation.lock().unwrap().name().to_string(), None, Vec::new())
+            }
+            Operations::Saver(saver, _) => (saver.lock().unwrap().name().to_string(), None, Vec::new()),
+            Operations::Control(control, _) => {
+                let borrowed = control.lock().unwrap();
+                (borrowed.name().to_string(), loss_report(borrowed.loss()), borrowed.region_losses())
+            }
+        };
+
+        OperationReport {
+            name: label.clone().unwrap_or(name),
+            kind: self.kind(),
+            loss,
+            region_losses,
+        }
+    }
+
+    fn manifest(&self, label: &Option<String>, parameters: &BTreeMap<String, serde_json::Value>) -> OperationManifest {
+        let (apply, order) = match self {
+            Operations::Transformation(_, order) => {
+                let order = match order {
+                    Order::Normal => "Normal",
+                    Order::InverseFirst => "InverseFirst",
+                };
+                (None, Some(order.to_string()))
+            }
+            Operations::Saver(_, apply) | Operations::Control(_, apply) => {
+                let apply = match (*apply & Apply::FirstHalf != Apply::None, *apply & Apply::SecondHalf != Apply::None) {
+                    (true, true) => "Both",
+                    (true, false) => "FirstHalf",
+                    (false, true) => "SecondHalf",
+                    (false, false) => "None",
+                };
+                (Some(apply.to_string()), None)
+            }
+            Operations::Propagator(_) => (None, None),
+        };
+        let mut description = match self {
+            Operations::Propagator(propagator) => propagator.lock().unwrap().description(),
+            Operations::Transformation(transformation, _) => transformation.lock().unwrap().description(),
+            Operations::Saver(_, _) | Operations::Control(_, _) => OperationDescription::default(),
+        };
+        description.parameters.extend(parameters.clone());
+        let type_name = self.type_name();
+
+        OperationManifest {
+            name: label.clone().unwrap_or(type_name.clone()),
+            type_name,
+            kind: self.kind(),
+            apply,
+            order,
+            description,
+        }
+    }
+
+    /// Returns all loss checkers of the operation.
+    fn loss_checkers_mut(&mut self) -> Vec<&mut LossChecker> {
+        match self {
+            Operations::Propagator(propagator) => propagator.get_mut().unwrap().loss_mut().iter_mut().collect(),
+            Operations::Control(control, _) => control.get_mut().unwrap().loss_checkers_mut(),
+            Operations::Transformation(_, _) | Operations::Saver(_, _) => Vec::new(),
+        }
+    }
+
+    /// Performs the operation on the first half of the step if `first_half` is true, otherwise on the second half,
+    /// `time` is the start of the step in the first half and the end of the step in the second half.
+    /// Savers are skipped if `monitor` is false.
+    /// Returns whether the operation was actually performed.
+    fn perform(&mut self, wave_function: &mut WaveFunction, first_half: bool, time: f64, monitor: bool) -> bool {
+        let half = if first_half { Apply::FirstHalf } else { Apply::SecondHalf };
+
+        match self {
+            Operations::Propagator(propagator) => {
+                propagator.get_mut().unwrap().apply(wave_function, time);
+            }
+            Operations::Transformation(transformation, order) => {
+                let transformation = transformation.get_mut().unwrap();
+                match (order, first_half) {
+                    (Order::Normal, true) | (Order::InverseFirst, false) => transformation.transform(wave_function),
+                    (Order::Normal, false) | (Order::InverseFirst, true) => transformation.inverse_transform(wave_function),
+                }
+            }
+            Operations::Saver(saver, apply) => {
+                if !monitor || *apply & half == Apply::None {
+                    return false;
+                }
+                saver.get_mut().unwrap().monitor(wave_function, time)
+            }
+            Operations::Control(control, apply) => {
+                if *apply & half == Apply::None {
+                    return false;
+                }
+                if first_half {
+                    control.get_mut().unwrap().first_half(wave_function, time);
+                } else {
+                    control.get_mut().unwrap().second_half(wave_function, time);
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Accumulated state of the [`OperationStack`] restored after propagation used only as a diagnostic.
+struct StackSnapshot {
+    loss_checkers: Vec<LossChecker>,
+    profiler: Option<Profiler>,
+}
+
+fn loss_report(loss_checker: &Option<LossChecker>) -> Option<LossReport> {
+    loss_checker.as_ref().map(|loss| LossReport {
+        name: loss.name.clone(),
+        loss: loss.cumulative_loss(),
+    })
+}
+
 /// Operation stack defining split operator propagation step
 /// There are 4 types of operations:
 /// 1. Propagator - operator that implement [`Propagation`].
 /// 2. Transformation - operator that transform basis of `wave_function` and implement [`Diagonalization`].
 /// 3. Saver - saves states of `wave_function` during propagation and implement [`Saver`].
 /// 4. Control - other operations that control `wave_function` during propagation and implement [`Control`].
+///
+/// Each operation can be labeled with user supplied name using `name_last_operation`
+/// and described with its parameters using `describe_last_operation`,
+/// and wall time of each operation is recorded if profiling is enabled with `enable_profiling`.
 #[derive(Default)]
 pub struct OperationStack {
     stack: Vec<Operations>,
+    labels: Vec<Option<String>>,
+    parameters: Vec<BTreeMap<String, serde_json::Value>>,
+    profiler: Option<Profiler>,
 }
 
 impl OperationStack {
     pub fn new() -> Self {
         Self {
             stack: Vec::new(),
+            labels: Vec::new(),
+            parameters: Vec::new(),
+            profiler: None,
         }
     }
 
@@ -39,15 +206,21 @@ impl OperationStack {
         self.stack.len()
     }
 
+    fn push(&mut self, operation: Operations) {
+        self.stack.push(operation);
+        self.labels.push(None);
+        self.parameters.push(BTreeMap::new());
+    }
+
     /// Appends `Propagator` to the end of the operations.
     pub fn add_propagator(&mut self, propagator: Box<dyn Propagator + Send>) {
-        self.stack.push(Operations::Propagator(Mutex::new(propagator)));
+        self.push(Operations::Propagator(Mutex::new(propagator)));
     }
 
     /// Appends `Diagonalization` to the end of the operations.
     /// `order` is used to define the order of the transformations performed.
     pub fn add_transformation(&mut self, transformation: Box<dyn Transformation + Send>, order: Order) {
-        self.stack.push(Operations::Transformation(Mutex::new(transformation), order));
+        self.push(Operations::Transformation(Mutex::new(transformation), order));
     }
 
     /// Appends `Saver` to the end of the operations. 
@@ -55,12 +228,84 @@ impl OperationStack {
     pub fn add_saver(&mut self, saver: Box<dyn Saver + Send>, apply: Apply) {
         assert!(apply != Apply::FirstHalf & Apply::SecondHalf);
 
-        self.stack.push(Operations::Saver(Mutex::new(saver), apply));
+        self.push(Operations::Saver(Mutex::new(saver), apply));
     }
 
     /// Appends `Control` to the end of the operations. `apply` is used to define when `Control` should be applied.
     pub fn add_control(&mut self, control: Box<dyn Control + Send>, apply: Apply) {
-        self.stack.push(Operations::Control(Mutex::new(control), apply));
+        self.push(Operations::Control(Mutex::new(control), apply));
+    }
+
+    /// Labels the last appended operation with given `name` used in reports and profiling.
+    pub fn name_last_operation(&mut self, name: &str) {
+        let label = self.labels.last_mut().expect("There is no operation to name");
+        *label = Some(name.to_string());
+    }
+
+    /// Adds `parameters` of the last appended operation written to its description in the [`RunManifest`],
+    /// e.g. the mass of the kinetic term or the [`AbsorbingPotential`](crate::hamiltonian_factory::absorbing_potentials::AbsorbingPotential)
+    /// it was created from. `parameters` have to serialize to JSON object.
+    pub fn describe_last_operation<T: Serialize>(&mut self, parameters: T) {
+        let parameters = serde_json::to_value(parameters).expect("Parameters have to be serializable to JSON");
+        let serde_json::Value::Object(parameters) = parameters else {
+            panic!("Parameters have to serialize to JSON object");
+        };
+        let described = self.parameters.last_mut().expect("There is no operation to describe");
+
+        described.extend(parameters);
+    }
+
+    /// Enables recording of wall time and number of calls of each operation.
+    pub fn enable_profiling(&mut self) {
+        self.profiler = Some(Profiler::new(self.stack.len()));
+    }
+
+    /// Disables recording of wall time and number of calls of each operation.
+    pub fn disable_profiling(&mut self) {
+        self.profiler = None;
+    }
+
+    /// Returns copies of the loss checkers and the profiler of all operations.
+    fn snapshot(&mut self) -> StackSnapshot {
+        StackSnapshot {
+            loss_checkers: self.stack.iter_mut()
+                .flat_map(|op| op.loss_checkers_mut())
+                .map(|loss| loss.clone())
+                .collect(),
+            profiler: self.profiler.clone(),
+        }
+    }
+
+    /// Restores the loss checkers and the profiler from `snapshot` taken on the same operations.
+    fn restore(&mut self, snapshot: StackSnapshot) {
+        let loss_checkers = self.stack.iter_mut().flat_map(|op| op.loss_checkers_mut());
+        for (loss, saved) in loss_checkers.zip(snapshot.loss_checkers) {
+            *loss = saved;
+        }
+        self.profiler = snapshot.profiler;
+    }
+
+    /// Returns profiles of each operation in the stack order if profiling is enabled.
+    pub fn profiles(&self) -> Option<Vec<OperationProfile>> {
+        let profiler = self.profiler.as_ref()?;
+
+        let profiles = self.stack.iter()
+            .zip(self.labels.iter())
+            .enumerate()
+            .map(|(i, (op, label))| {
+                let type_name = op.type_name();
+
+                OperationProfile {
+                    name: label.clone().unwrap_or(type_name.clone()),
+                    type_name,
+                    kind: op.kind(),
+                    calls: profiler.calls(i),
+                    elapsed: profiler.elapsed(i).as_secs_f64(),
+                }
+            })
+            .collect();
+
+        Some(profiles)
     }
 }
 
@@ -68,13 +313,20 @@ impl OperationStack {
 /// Building the split-operator method is done by appending operations in the order they should be performed,
 /// last appended operation should be the central one with full step.
 ///
-/// With supplied [`WaveFunction`] and [`TimeGrid`] using setters, propagation is performed by calling `propagate` method.
+/// With supplied [`WaveFunction`] and [`TimeGrid`] or [`TimeSchedule`] using setters, propagation is performed by calling `propagate` method.
+/// `Propagation` keeps the current time that is passed to the operations and advanced with each step.
 /// For example implementation of Propagation see `NeOcs` struct and `Animation` that builds NeOcs and propagate it.
+/// Progress of the propagation is reported only if [`Reporter`] is set.
 #[derive(Default)]
 pub struct Propagation {
     wave_function: WaveFunction,
     time_grid: TimeGrid,
+    time_schedule: Option<TimeSchedule>,
+    time: f64,
     operation_stack: OperationStack,
+    reporter: Option<Box<dyn Reporter + Send>>,
+    stop_conditions: Vec<(Box<dyn StopCondition + Send>, usize)>,
+    elapsed: ElapsedTimes,
 }
 
 impl Propagation {
@@ -83,7 +335,12 @@ impl Propagation {
         Propagation {
             wave_function,
             time_grid,
+            time_schedule: None,
+            time: 0.0,
             operation_stack,
+            reporter: None,
+            stop_conditions: Vec::new(),
+            elapsed: ElapsedTimes::default(),
         }
     }
 
@@ -92,8 +349,88 @@ impl Propagation {
         self.wave_function = wave_function;
     }
 
-    /// Sets new `TimeGrid` to be used in propagation. Be aware that appended operations might have depended on previous `time_step` in `TimeGrid`.
+    /// Sets new `TimeGrid` to be used in propagation.
+    /// Propagators created from hamiltonians are regenerated and controls are adapted to the new time step,
+    /// so that e.g. imaginary time relaxation can be followed by real time propagation.
+    ///
+    /// # Panics
+    /// Panics if the time step changes and any propagator does not follow the `TimeGrid`,
+    /// e.g. operator set explicitly with `set_operator` instead of generated from a hamiltonian.
     pub fn set_time_grid(&mut self, time_grid: TimeGrid) {
+        self.time_schedule = None;
+        self.switch_time_grid(time_grid);
+    }
+
+    /// Sets non uniform [`TimeSchedule`] to be used in propagation,
+    /// operations are regenerated for each of its segments as in `set_time_grid`.
+    ///
+    /// # Panics
+    /// Panics if the segments differ in time step and any propagator does not follow the `TimeGrid`.
+    pub fn set_time_schedule(&mut self, time_schedule: TimeSchedule) {
+        self.assert_follows_time_grids(time_schedule.segments());
+        self.switch_time_grid(time_schedule.segments()[0].clone());
+        self.time_schedule = Some(time_schedule);
+    }
+
+    /// Returns [`TimeSchedule`] used in propagation, consisting of the single `TimeGrid` if no schedule was set.
+    pub fn time_schedule(&self) -> TimeSchedule {
+        match &self.time_schedule {
+            Some(time_schedule) => time_schedule.clone(),
+            None => self.time_grid.clone().into(),
+        }
+    }
+
+    /// Returns the current time of the propagation.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Sets the current time of the propagation.
+    pub fn set_time(&mut self, time: f64) {
+        self.time = time;
+    }
+
+    /// Returns whether switching to `time_grid` requires regenerating the operations.
+    fn changes_step(&self, time_grid: &TimeGrid) -> bool {
+        time_grid.step != self.time_grid.step || time_grid.im_time != self.time_grid.im_time
+    }
+
+    /// Checks that all propagators follow the switches to `time_grids` before any of them is performed.
+    fn assert_follows_time_grids(&self, time_grids: &[TimeGrid]) {
+        if time_grids.iter().all(|time_grid| !self.changes_step(time_grid)) {
+            return;
+        }
+
+        for (op, label) in self.operation_stack.stack.iter().zip(self.operation_stack.labels.iter()) {
+            if let Operations::Propagator(propagator) = op {
+                let propagator = propagator.lock().unwrap();
+                assert!(
+                    propagator.follows_time_grid(),
+                    "Propagator {} does not follow the time grid change, create it from a hamiltonian",
+                    label.as_deref().unwrap_or(propagator.name())
+                );
+            }
+        }
+    }
+
+    /// Updates the operations to `time_grid` if it differs from the current one.
+    fn switch_time_grid(&mut self, time_grid: TimeGrid) {
+        if !self.changes_step(&time_grid) {
+            self.time_grid = time_grid;
+            return;
+        }
+        self.assert_follows_time_grids(std::slice::from_ref(&time_grid));
+
+        for op in self.operation_stack.stack.iter_mut() {
+            match op {
+                Operations::Propagator(propagator) => {
+                    propagator.get_mut().unwrap().update_time_grid(&self.time_grid, &time_grid)
+                }
+                Operations::Control(control, _) => control.get_mut().unwrap().update_time_grid(&self.time_grid, &time_grid),
+                _ => {}
+            }
+        }
+
         self.time_grid = time_grid;
     }
 
@@ -102,7 +439,24 @@ impl Propagation {
         self.operation_stack = operation_stack;
     }
 
-    /// Returns reference to `TimeGrid` used in propagation.
+    /// Sets `Reporter` that reports the progress of the propagation.
+    pub fn set_reporter(&mut self, reporter: Box<dyn Reporter + Send>) {
+        self.reporter = Some(reporter);
+    }
+
+    /// Adds [`StopCondition`] checked every `interval` steps that terminates the propagation early if it is met.
+    pub fn add_stop_condition(&mut self, stop_condition: Box<dyn StopCondition + Send>, interval: usize) {
+        assert!(interval > 0, "Interval has to be positive");
+
+        self.stop_conditions.push((stop_condition, interval));
+    }
+
+    /// Removes all stop conditions, so that all steps of the time grid are performed.
+    pub fn clear_stop_conditions(&mut self) {
+        self.stop_conditions.clear();
+    }
+
+    /// Returns reference to the current `TimeGrid` used in propagation.
     pub fn time_grid(&self) -> &TimeGrid {
         &self.time_grid
     }
@@ -121,75 +475,213 @@ impl Propagation {
         }
     }
 
-    /// Resets all losses that were observed by `Propagator` with enabled loss checking.
+    /// Resets all losses that were observed by `Propagator` and `Control` with enabled loss checking.
     pub fn reset_losses(&mut self) {
-        for op in &mut self.operation_stack.stack {
-            if let Operations::Propagator(propagator) = op {
-                propagator.lock().unwrap().loss_reset();
-            }
-        }
-    }
-
-    /// Performs one step in propagation.
-    fn step(&mut self) {
         for op in &mut self.operation_stack.stack {
             match op {
                 Operations::Propagator(propagator) => {
-                    propagator.lock().unwrap().apply(&mut self.wave_function);
-                }
-                Operations::Transformation(transformation, order) => {
-                    match order {
-                        Order::Normal => transformation.lock().unwrap().transform(&mut self.wave_function),
-                        Order::InverseFirst => transformation.lock().unwrap().inverse_transform(&mut self.wave_function),
-                    }
-                }
-                Operations::Saver(saver, apply) => {
-                    if *apply & Apply::FirstHalf != Apply::None {
-                        saver.lock().unwrap().monitor(&mut self.wave_function)
-                    };
+                    propagator.get_mut().unwrap().loss_reset();
                 }
-                Operations::Control(control, apply) => {
-                    if *apply & Apply::FirstHalf != Apply::None {
-                        control.lock().unwrap().first_half(&mut self.wave_function);
+                Operations::Control(control, _) => {
+                    if let Some(loss) = control.get_mut().unwrap().loss_mut() {
+                        loss.reset();
                     }
                 }
+                _ => {}
             }
         }
+    }
 
-        for op in &mut self.operation_stack.stack.iter().rev().skip(1) {
-            match op {
-                Operations::Propagator(propagator) => {
-                    propagator.lock().unwrap().apply(&mut self.wave_function);
-                }
-                Operations::Transformation(transformation, order) => {
-                    match order {
-                        Order::Normal => transformation.lock().unwrap().inverse_transform(&mut self.wave_function),
-                        Order::InverseFirst => transformation.lock().unwrap().transform(&mut self.wave_function),
-                    }
-                }
-                Operations::Saver(saver, apply) => {
-                    if *apply & Apply::SecondHalf != Apply::None {
-                        saver.lock().unwrap().monitor(&mut self.wave_function)
-                    };
+    /// Performs one step in propagation advancing the current time, savers are skipped if `monitor` is false.
+    fn step_monitored(&mut self, monitor: bool) {
+        let operation_stack = &mut self.operation_stack;
+        let end_time = self.time + self.time_grid.step;
+
+        for (i, op) in operation_stack.stack.iter_mut().enumerate() {
+            let start = Instant::now();
+            if !op.perform(&mut self.wave_function, true, self.time, monitor) {
+                continue;
+            }
+
+            let duration = start.elapsed();
+            self.elapsed.add(op.kind(), duration);
+            if let Some(profiler) = &mut operation_stack.profiler {
+                profiler.record(i, duration);
+            }
+        }
+
+        for (i, op) in operation_stack.stack.iter_mut().enumerate().rev().skip(1) {
+            let start = Instant::now();
+            if !op.perform(&mut self.wave_function, false, end_time, monitor) {
+                continue;
+            }
+
+            let duration = start.elapsed();
+            self.elapsed.add(op.kind(), duration);
+            if let Some(profiler) = &mut operation_stack.profiler {
+                profiler.record(i, duration);
+            }
+        }
+
+        self.time = end_time;
+    }
+
+    /// Performs steps of all `segments` switching the operations to each segment,
+    /// returns the number of performed steps and the stop condition that terminated the propagation early.
+    /// Reporter, savers and stop conditions are used only if `monitor` is true.
+    fn run(&mut self, segments: &[TimeGrid], monitor: bool) -> (usize, Option<StopReport>) {
+        let mut step_no = 0;
+        if monitor {
+            self.stop_conditions.iter_mut().for_each(|(condition, _)| condition.reset());
+        }
+
+        self.assert_follows_time_grids(segments);
+        for segment in segments {
+            self.switch_time_grid(segment.clone());
+
+            for _ in 0..segment.step_no {
+                if let (true, Some(reporter)) = (monitor, &mut self.reporter) {
+                    reporter.step(step_no, self.time, &mut self.wave_function);
                 }
-                Operations::Control(control, apply) => {
-                    if *apply & Apply::SecondHalf != Apply::None {
-                        control.lock().unwrap().second_half(&mut self.wave_function);
+                self.step_monitored(monitor);
+                step_no += 1;
+
+                if monitor {
+                    if let Some(stop) = self.check_stop_conditions(step_no) {
+                        return (step_no, Some(stop));
                     }
                 }
             }
         }
+
+        (step_no, None)
+    }
+
+    /// Checks stop conditions due after `step_no` steps, returns the first one that is met.
+    fn check_stop_conditions(&mut self, step_no: usize) -> Option<StopReport> {
+        for (condition, interval) in self.stop_conditions.iter_mut() {
+            if step_no.is_multiple_of(*interval) && condition.check(self.time, &mut self.wave_function) {
+                return Some(StopReport {
+                    condition: condition.name().to_string(),
+                    step_no,
+                    time: self.time,
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Performs propagation of the `wave_function` for the time given by `TimeGrid` or `TimeSchedule`,
+    /// or until one of the stop conditions is met.
+    /// Returns [`PropagationReport`] summarizing the propagation.
+    pub fn propagate(&mut self) -> PropagationReport {
+        let time_schedule = self.time_schedule();
+
+        self.propagate_segments(time_schedule.segments())
+    }
+
+    /// Moves the propagation to a worker thread and performs `propagate` there.
+    /// Returns [`PropagationHandle`] to query the progress, pause, cancel or take snapshots of the wave function,
+    /// the `Propagation` with its report is given back when the handle is joined.
+    pub fn spawn(mut self) -> PropagationHandle {
+        let shared = Arc::new(SharedState::default());
+        self.stop_conditions.insert(0, (Box::new(HandleCondition::new(shared.clone())), 1));
+
+        let thread = std::thread::spawn(move || {
+            let report = self.propagate();
+            self.stop_conditions.remove(0);
+
+            (self, report)
+        });
+
+        PropagationHandle::new(shared, thread)
+    }
+
+    /// Performs propagation of the `wave_function` backward in time for the time given by `TimeGrid` or `TimeSchedule`,
+    /// propagators are regenerated with reversed time step and restored afterwards.
+    /// For symmetric splittings propagation forward and then backward recovers the initial wave function.
+    /// Only real time propagation can be reversed.
+    ///
+    /// # Panics
+    /// Panics in imaginary time or if any propagator does not follow the `TimeGrid`,
+    /// e.g. operator set explicitly with `set_operator` that would keep propagating forward.
+    pub fn propagate_backward(&mut self) -> PropagationReport {
+        let time_schedule = self.time_schedule();
+        assert!(
+            time_schedule.segments().iter().all(|segment| !segment.im_time),
+            "Backward propagation is possible only in real time"
+        );
+
+        let time_grid = self.time_grid.clone();
+        let report = self.propagate_segments(time_schedule.reversed().segments());
+        self.switch_time_grid(time_grid);
+
+        report
+    }
+
+    fn propagate_segments(&mut self, segments: &[TimeGrid]) -> PropagationReport {
+        self.elapsed = ElapsedTimes::default();
+
+        let (step_no, stop) = self.run(segments, true);
+
+        let mut report = self.report(step_no);
+        report.stop = stop;
+        if let Some(reporter) = &mut self.reporter {
+            reporter.finish(&report);
+        }
+
+        report
     }
 
-    /// Performs propagation of the `wave_function` for the time given by `TimeGrid`.
-    pub fn propagate(&mut self) {
-        for i in 0..self.time_grid.step_no {
-            println!("step no: {}, time: {}", i, i as f64 * self.time_grid.step);
-            self.step();
+    /// Returns relative norm of the difference between the wave function and the wave function
+    /// propagated forward and then backward by the `TimeGrid`, used as an accuracy diagnostic of the operation stack.
+    /// Savers are skipped and the wave function, time, losses and profiles are restored afterwards.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as `propagate_backward`.
+    pub fn round_trip_error(&mut self) -> f64 {
+        let time_schedule = self.time_schedule();
+        assert!(
+            time_schedule.segments().iter().all(|segment| !segment.im_time),
+            "Backward propagation is possible only in real time"
+        );
+
+        let mut initial = self.wave_function.clone();
+        let time_grid = self.time_grid.clone();
+        let time = self.time;
+        let snapshot = self.operation_stack.snapshot();
+        let elapsed = self.elapsed.clone();
+
+        self.run(time_schedule.segments(), false);
+        self.run(time_schedule.reversed().segments(), false);
+        self.switch_time_grid(time_grid);
+        self.time = time;
+        self.operation_stack.restore(snapshot);
+        self.elapsed = elapsed;
+
+        let mut difference = std::mem::replace(&mut self.wave_function, initial.clone());
+        *difference.array_mut() -= initial.array();
+
+        (difference.norm() / initial.norm()).sqrt()
+    }
+
+    /// Returns [`PropagationReport`] of the current state of the propagation after `step_no` steps.
+    fn report(&mut self, step_no: usize) -> PropagationReport {
+        PropagationReport {
+            step_no,
+            operations: self.operation_stack.stack.iter()
+                .zip(self.operation_stack.labels.iter())
+                .map(|(op, label)| op.report(label))
+                .collect(),
+            final_norm: self.wave_function.norm(),
+            elapsed: self.elapsed.clone(),
+            stop: None,
         }
     }
 
-    /// Prints losses that were observed by `Propagator` with enabled loss checking.
+    /// Prints losses that were observed by `Propagator` and `Control` with enabled loss checking.
+    #[deprecated(note = "use `get_losses` or the losses in `PropagationReport` printed by `PrintReporter`")]
     pub fn print_losses(&mut self) {
         for op in &mut self.operation_stack.stack {
             match op {
@@ -212,7 +704,7 @@ impl Propagation {
         }
     }
 
-    /// Returns losses that were observed by `Propagator` with enabled loss checking.
+    /// Returns losses that were observed by `Propagator` and `Control` with enabled loss checking.
     pub fn get_losses(&mut self) -> Vec<f64> {
         let mut losses = Vec::new();
         for op in &mut self.operation_stack.stack {
@@ -224,12 +716,59 @@ impl Propagation {
                         losses.push(loss.loss());
                     }
                 }
+                Operations::Control(control, _) => {
+                    let borrowed = control.lock().unwrap();
+                    let loss_checker = borrowed.loss();
+                    if let Some(loss) = loss_checker {
+                        losses.push(loss.loss());
+                    }
+                }
                 _ => {}
             }
         }
         losses
     }
 
+    /// Returns profiles of each operation in the stack order if profiling of `OperationStack` is enabled.
+    pub fn profiles(&self) -> Option<Vec<OperationProfile>> {
+        self.operation_stack.profiles()
+    }
+
+    /// Returns table of wall times and call counts of each operation if profiling of `OperationStack` is enabled.
+    pub fn profile_table(&self) -> Option<String> {
+        self.operation_stack.profiles().map(|profiles| profile_table(&profiles))
+    }
+
+    /// Resets recorded wall times and call counts of each operation.
+    pub fn reset_profiles(&mut self) {
+        if let Some(profiler) = &mut self.operation_stack.profiler {
+            profiler.reset();
+        }
+    }
+
+    /// Saves distributions of the lost norm observed by `Propagator` and `Control` with enabled loss distribution.
+    pub fn save_loss_distributions(&self) -> Result<(), &str> {
+        for op in &self.operation_stack.stack {
+            let saved = match op {
+                Operations::Propagator(propagator) => {
+                    let borrowed = propagator.lock().unwrap();
+                    borrowed.loss().as_ref().and_then(|x| x.distribution().as_ref()).map(|x| x.save().is_ok())
+                }
+                Operations::Control(control, _) => {
+                    let borrowed = control.lock().unwrap();
+                    borrowed.loss().as_ref().and_then(|x| x.distribution().as_ref()).map(|x| x.save().is_ok())
+                }
+                _ => None,
+            };
+
+            if saved == Some(false) {
+                return Err("Failed to save loss distribution");
+            }
+        }
+
+        Ok(())
+    }
+
     /// Saves states of `wave_function` during propagation observed by all `Saver`.
     pub fn savers_save(&mut self) {
         for op in &mut self.operation_stack.stack {
@@ -242,7 +781,84 @@ impl Propagation {
         }
     }
 
+    /// Returns data collected by all `Saver` operations as named arrays.
+    pub fn saver_outputs(&self) -> Vec<(String, ArrayD<f64>)> {
+        self.operation_stack.stack.iter()
+            .filter_map(|op| match op {
+                Operations::Saver(saver, _) => Some(saver.lock().unwrap().outputs()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Returns names of the data collected by all `Saver` operations without copying the data.
+    pub fn saver_output_names(&self) -> Vec<String> {
+        self.operation_stack.stack.iter()
+            .filter_map(|op| match op {
+                Operations::Saver(saver, _) => Some(saver.lock().unwrap().output_names()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Returns [`RunManifest`] describing the grids, time grid and operations of the run with given `name`,
+    /// together with the losses and wall times from the `report` of the run.
+    pub fn manifest(&self, name: &str, report: &PropagationReport) -> RunManifest {
+        RunManifest {
+            name: name.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            grids: self.wave_function.grids().to_vec(),
+            time_schedule: self.time_schedule().segments().to_vec(),
+            time: self.time,
+            operations: self.operation_stack.stack.iter()
+                .zip(self.operation_stack.labels.iter())
+                .zip(self.operation_stack.parameters.iter())
+                .map(|((op, label), parameters)| op.manifest(label, parameters))
+                .collect(),
+            report: report.clone(),
+            profiles: self.profiles(),
+            outputs: self.saver_output_names(),
+            metadata: Default::default(),
+        }
+    }
+
+    /// Saves [`RunManifest`] of the run with given `name` in current directory, next to the saver outputs.
+    pub fn save_manifest(&self, name: &str, report: &PropagationReport) -> Result<(), &str> {
+        if self.manifest(name, report).save().is_err() {
+            return Err("Failed to save manifest");
+        }
+
+        Ok(())
+    }
+
+    /// Returns energy statistics of the current wave function with respect to `hamiltonian`
+    /// without performing a step, so neither the wave function nor the savers are affected.
+    pub fn energy_statistics(&self, hamiltonian: &mut Hamiltonian) -> EnergyStatistics {
+        hamiltonian.energy_statistics(&self.wave_function)
+    }
+
+    /// Returns mean energy estimated from the norm decay or the phase change of the wave function during a single step.
+    /// The step is performed without savers and the wave function, current time, losses and profiles are restored afterwards.
     pub fn mean_energy(&mut self) -> f64 {
+        let wave_function = self.wave_function.clone();
+        let time = self.time;
+        let snapshot = self.operation_stack.snapshot();
+        let elapsed = self.elapsed.clone();
+
+        let energy = self.step_energy();
+
+        self.wave_function = wave_function;
+        self.time = time;
+        self.operation_stack.restore(snapshot);
+        self.elapsed = elapsed;
+
+        energy
+    }
+
+    /// Performs a step without savers and returns the mean energy estimated from the change of the wave function.
+    fn step_energy(&mut self) -> f64 {
         if self.time_grid.im_time == true {
             match &self.operation_stack.stack[0] {
                 Operations::Control(control, _) => {
@@ -261,7 +877,7 @@ impl Propagation {
                 }
                 _ => panic!(""),
             }
-            self.step();
+            self.step_monitored(false);
 
             let decay = match &self.operation_stack.stack[0] {
                 Operations::Control(control, _) => {
@@ -282,7 +898,7 @@ impl Propagation {
             energy
         } else {
             let mut wave_before = self.wave_function.clone();
-            self.step();
+            self.step_monitored(false);
             let mut wave_after = self.wave_function.clone();
 
             let energy = -wave_after.dot(&mut wave_before).arg() / self.time_grid.step;
@@ -0,0 +1,161 @@
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    thread::JoinHandle,
+};
+
+use crate::{
+    propagation::Propagation, report::PropagationReport, stop_condition::StopCondition, wave_function::WaveFunction,
+};
+
+/// Progress of the propagation running on the worker thread after `step_no` steps at given `time`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Progress {
+    pub step_no: usize,
+    pub time: f64,
+    pub norm: f64,
+}
+
+#[derive(Default)]
+struct State {
+    progress: Progress,
+    paused: bool,
+    cancelled: bool,
+    finished: bool,
+    snapshot_requested: bool,
+    snapshot: Option<WaveFunction>,
+}
+
+/// State shared between [`PropagationHandle`] and the propagation on the worker thread.
+#[derive(Default)]
+pub(crate) struct SharedState {
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+impl SharedState {
+    fn finish(&self) {
+        self.state.lock().unwrap().finished = true;
+        self.condvar.notify_all();
+    }
+}
+
+/// Stop condition checked after each step of the spawned propagation,
+/// it publishes the progress, serves snapshots and blocks while the propagation is paused.
+/// The propagation is marked as finished when the condition is dropped.
+pub(crate) struct HandleCondition {
+    shared: Arc<SharedState>,
+    step_no: usize,
+}
+
+impl HandleCondition {
+    pub(crate) fn new(shared: Arc<SharedState>) -> Self {
+        HandleCondition { shared, step_no: 0 }
+    }
+}
+
+impl StopCondition for HandleCondition {
+    fn name(&self) -> &str {
+        "Cancellation"
+    }
+
+    fn check(&mut self, time: f64, wave_function: &mut WaveFunction) -> bool {
+        self.step_no += 1;
+
+        let mut state = self.shared.state.lock().unwrap();
+        state.progress = Progress {
+            step_no: self.step_no,
+            time,
+            norm: wave_function.norm(),
+        };
+
+        loop {
+            if state.snapshot_requested {
+                state.snapshot = Some(wave_function.clone());
+                state.snapshot_requested = false;
+                self.shared.condvar.notify_all();
+            }
+            if state.cancelled {
+                return true;
+            }
+            if !state.paused {
+                return false;
+            }
+
+            state = self.shared.condvar.wait(state).unwrap();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.step_no = 0;
+    }
+}
+
+impl Drop for HandleCondition {
+    fn drop(&mut self) {
+        self.shared.finish();
+    }
+}
+
+/// Handle to the propagation running on the worker thread created by [`Propagation::spawn`].
+/// Requests are served at the next step boundary of the propagation.
+pub struct PropagationHandle {
+    shared: Arc<SharedState>,
+    thread: JoinHandle<(Propagation, PropagationReport)>,
+}
+
+impl PropagationHandle {
+    pub(crate) fn new(shared: Arc<SharedState>, thread: JoinHandle<(Propagation, PropagationReport)>) -> Self {
+        PropagationHandle { shared, thread }
+    }
+
+    /// Returns the progress of the propagation at the last step boundary.
+    pub fn progress(&self) -> Progress {
+        self.shared.state.lock().unwrap().progress
+    }
+
+    /// Returns true if the propagation has finished.
+    pub fn is_finished(&self) -> bool {
+        self.shared.state.lock().unwrap().finished
+    }
+
+    /// Requests a pause of the propagation at the next step boundary.
+    pub fn pause(&self) {
+        self.shared.state.lock().unwrap().paused = true;
+    }
+
+    /// Resumes the paused propagation.
+    pub fn resume(&self) {
+        self.shared.state.lock().unwrap().paused = false;
+        self.shared.condvar.notify_all();
+    }
+
+    /// Requests the propagation to stop at the next step boundary,
+    /// the cancellation is recorded as the stop condition in the [`PropagationReport`].
+    pub fn cancel(&self) {
+        self.shared.state.lock().unwrap().cancelled = true;
+        self.shared.condvar.notify_all();
+    }
+
+    /// Returns a copy of the wave function taken at the next step boundary,
+    /// or `None` if the propagation has finished before.
+    pub fn snapshot(&self) -> Option<WaveFunction> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.finished {
+            return None;
+        }
+
+        state.snapshot_requested = true;
+        self.shared.condvar.notify_all();
+        while state.snapshot.is_none() && !state.finished {
+            state = self.shared.condvar.wait(state).unwrap();
+        }
+        state.snapshot_requested = false;
+
+        state.snapshot.take()
+    }
+
+    /// Waits for the propagation to finish and returns the `Propagation` with its [`PropagationReport`].
+    pub fn join(self) -> (Propagation, PropagationReport) {
+        self.thread.join().expect("Propagation thread panicked")
+    }
+}
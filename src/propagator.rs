@@ -1,18 +1,47 @@
 pub mod transformation;
+pub mod adiabatic_transformation;
+pub mod dct_transformation;
+pub mod dst_transformation;
 pub mod fft_transformation;
+pub mod mapped_kinetic_propagator;
+pub mod mapped_transformation;
 pub mod matrix_transformation;
+pub mod multi_fft_transformation;
 pub mod n_dim_propagator;
 pub mod one_dim_propagator;
+pub(crate) mod operator_generator;
 pub mod propagator_factory;
 pub mod non_diagonal_propagator;
 pub mod state_matrix_transformation;
 
-use crate::{loss_checker::LossChecker, wave_function::WaveFunction};
+use crate::{loss_checker::LossChecker, manifest::OperationDescription, time_grid::TimeGrid, wave_function::WaveFunction};
 
 pub trait Propagator {
-    fn apply(&mut self, wave_function: &mut WaveFunction);
+    /// Returns the name of the propagator.
+    fn name(&self) -> &str;
+
+    /// Applies the propagator to the wave function, `time` is the start of the time step in the first half
+    /// and the end of the time step in the second half.
+    fn apply(&mut self, wave_function: &mut WaveFunction, time: f64);
 
     fn loss(&self) -> &Option<LossChecker>;
 
+    fn loss_mut(&mut self) -> &mut Option<LossChecker>;
+
     fn loss_reset(&mut self);
+
+    /// Returns whether the operator is regenerated in `update_time_grid` when the [`TimeGrid`] changes.
+    /// Propagation panics on switching the time grid, e.g. to real or backward time, if any propagator does not follow it.
+    fn follows_time_grid(&self) -> bool {
+        false
+    }
+
+    /// Regenerates the operator from its generating hamiltonian after the [`TimeGrid`] changed from `previous` to `time_grid`.
+    /// Called only if `follows_time_grid` is true.
+    fn update_time_grid(&mut self, _previous: &TimeGrid, _time_grid: &TimeGrid) {}
+
+    /// Returns the description of the propagator written into the run manifest.
+    fn description(&self) -> OperationDescription {
+        OperationDescription::default()
+    }
 }
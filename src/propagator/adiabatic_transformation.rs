@@ -0,0 +1,136 @@
+use crate::{grid::Grid, linear_algebra::hermitian_eigen, manifest::OperationDescription, wave_function::WaveFunction};
+
+use super::{state_matrix_transformation::StateMatrixTransformation, transformation::Transformation};
+use ndarray::{Array1, Array2, ArrayD, Axis};
+use num::complex::Complex64;
+use rayon::prelude::*;
+
+/// Transformation from diabatic channels to adiabatic basis diagonalizing the channel potential matrix at each radial point.
+/// Eigenvectors are sorted by energy and their phases are fixed continuously along the radial grid.
+/// Savers placed between the transformation and its inverse record the wave function in the adiabatic basis.
+#[derive(Clone)]
+pub struct AdiabaticTransformation {
+    radial_dimension_no: usize,
+    channel_dimension_no: usize,
+    energies: Array2<f64>,
+    transformation: StateMatrixTransformation,
+}
+
+impl AdiabaticTransformation {
+    /// Creates new [`AdiabaticTransformation`] of `channel_grid` diagonalizing hermitian `potential` evaluated at the nodes of `radial_grid`,
+    /// the channel grid is transformed into the adiabatic state index grid named `name`.
+    pub fn new<F>(potential: F, radial_grid: &Grid, channel_grid: &Grid, name: &str) -> Self
+    where
+        F: Fn(f64) -> Array2<Complex64> + Sync,
+    {
+        let channels_no = channel_grid.nodes_no;
+
+        let mut eigens: Vec<(Vec<f64>, Array2<Complex64>)> = radial_grid.nodes
+            .par_iter()
+            .map(|&r| {
+                let matrix = potential(r);
+                assert!(
+                    matrix.shape() == [channels_no, channels_no],
+                    "Potential matrix has to be square with the size of the channel grid"
+                );
+                let (energies, vectors) = hermitian_eigen(&matrix);
+
+                (energies.to_vec(), vectors)
+            })
+            .collect();
+
+        for i in 0..eigens.len() {
+            let (previous, current) = eigens.split_at_mut(i);
+            let vectors = &mut current[0].1;
+
+            for (j, mut column) in vectors.columns_mut().into_iter().enumerate() {
+                // overlap with previous point, or largest component at the first point
+                let reference = match previous.last() {
+                    Some((_, previous_vectors)) => previous_vectors.column(j)
+                        .iter()
+                        .zip(column.iter())
+                        .map(|(p, c)| p.conj() * c)
+                        .sum::<Complex64>(),
+                    None => *column.iter()
+                        .max_by(|a, b| a.norm().total_cmp(&b.norm()))
+                        .unwrap(),
+                };
+
+                if reference.norm() > 0.0 {
+                    column *= reference.conj() / reference.norm();
+                }
+            }
+        }
+
+        let mut energies = Array2::zeros((radial_grid.nodes_no, channels_no));
+        let mut transformations = Vec::with_capacity(eigens.len());
+        let mut inverse_transformations = Vec::with_capacity(eigens.len());
+        for ((values, vectors), mut energy_row) in eigens.into_iter().zip(energies.rows_mut()) {
+            energy_row.assign(&Array1::from_vec(values));
+            transformations.push(vectors.t().mapv(|x| x.conj()));
+            inverse_transformations.push(vectors);
+        }
+
+        let adiabatic_grid = Grid::new_custom(
+            name,
+            (0..channels_no).map(|i| i as f64).collect(),
+            vec![1.0; channels_no],
+            channel_grid.dimension_no,
+        );
+
+        let mut transformation = StateMatrixTransformation::new(radial_grid.dimension_no, channel_grid, adiabatic_grid);
+        transformation.set_diagonalization_matrices(transformations, inverse_transformations);
+
+        Self {
+            radial_dimension_no: radial_grid.dimension_no,
+            channel_dimension_no: channel_grid.dimension_no,
+            energies,
+            transformation,
+        }
+    }
+
+    /// Returns adiabatic energies with radial points along rows and adiabatic states along columns.
+    pub fn energies(&self) -> &Array2<f64> {
+        &self.energies
+    }
+
+    /// Returns adiabatic energies broadcast to the shape of `example_wave_function`,
+    /// to be used with [`super::propagator_factory::n_dim_into_propagator`].
+    pub fn adiabatic_potential(&self, example_wave_function: &WaveFunction) -> ArrayD<f64> {
+        let mut potential = ArrayD::zeros(example_wave_function.array().raw_dim());
+        let (first, second) = if self.radial_dimension_no < self.channel_dimension_no {
+            (self.radial_dimension_no, self.channel_dimension_no)
+        } else {
+            (self.channel_dimension_no, self.radial_dimension_no)
+        };
+        let energies = if first == self.radial_dimension_no { self.energies.view() } else { self.energies.t() };
+
+        for (i, mut block) in potential.axis_iter_mut(Axis(first)).enumerate() {
+            for (j, mut sub_block) in block.axis_iter_mut(Axis(second - 1)).enumerate() {
+                sub_block.fill(energies[[i, j]]);
+            }
+        }
+
+        potential
+    }
+}
+
+impl Transformation for AdiabaticTransformation {
+    fn name(&self) -> &str {
+        "AdiabaticTransformation"
+    }
+
+    #[inline(always)]
+    fn transform(&mut self, wave_function: &mut WaveFunction) {
+        self.transformation.transform(wave_function);
+    }
+
+    #[inline(always)]
+    fn inverse_transform(&mut self, wave_function: &mut WaveFunction) {
+        self.transformation.inverse_transform(wave_function);
+    }
+
+    fn description(&self) -> OperationDescription {
+        self.transformation.description()
+    }
+}
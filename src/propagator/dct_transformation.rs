@@ -0,0 +1,108 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use crate::{grid::Grid, manifest::OperationDescription, wave_function::WaveFunction};
+
+use super::{fft_transformation::fft_extended_lanes, transformation::Transformation};
+use num::complex::Complex64;
+use rustfft::{Fft, FftPlanner};
+
+/// Diagonalization to operator eigenspace using discrete cosine transformation (DCT-I),
+/// suitable for walls with Neumann boundary conditions.
+///
+/// The grid has to be uniform with the walls placed at the first and the last node,
+/// its weights should be the trapezoid weights of [`Grid::new_linear_continuos`].
+/// Transformed grid contains wave numbers k_n = n π / L for n = 0, ..., N - 1,
+/// with weights such that the norm of the wave function is preserved.
+#[derive(Clone)]
+pub struct DCTTransformation {
+    dimension_no: usize,
+    dimension_size: usize,
+
+    fft: Box<Arc<dyn Fft<f64>>>,
+    transform_factor: f64,
+    inverse_factor: f64,
+
+    pub grid_transformation: Grid,
+}
+
+impl DCTTransformation {
+    /// Creates new [`DCTTransformation`] along given grid that transforms this grid into new grid with name `transformed_grid_name`.
+    pub fn new(grid: &Grid, transformed_grid_name: &str) -> Self {
+        let nodes_no = grid.nodes_no;
+        assert!(nodes_no > 1, "Grid needs at least 2 nodes");
+
+        let fft = FftPlanner::new().plan_fft_forward(2 * (nodes_no - 1));
+
+        let step = (grid.nodes.last().unwrap() - grid.nodes.first().unwrap()) / (nodes_no as f64 - 1.0);
+        let momentum_step = PI / (step * (nodes_no as f64 - 1.0));
+        let momenta: Vec<f64> = (0..nodes_no).map(|n| n as f64 * momentum_step).collect();
+        let mut weights = vec![momentum_step; nodes_no];
+        weights[0] *= 0.5;
+        weights[nodes_no - 1] *= 0.5;
+
+        let grid_transformation = Grid::new_custom(transformed_grid_name, momenta, weights, grid.dimension_no);
+
+        DCTTransformation {
+            dimension_no: grid.dimension_no,
+            dimension_size: nodes_no,
+            fft: Box::new(fft),
+            transform_factor: step * (2.0 / PI).sqrt(),
+            inverse_factor: momentum_step * (2.0 / PI).sqrt(),
+            grid_transformation,
+        }
+    }
+
+    /// Applies DCT-I multiplied by `factor` along the transformation axis.
+    fn cosine_transform(&self, wave_function: &mut WaveFunction, factor: f64) {
+        let size = self.dimension_size;
+        // DCT-I is given by the FFT of the even extension of the lane
+        let factor = Complex64::from(0.5 * factor);
+
+        fft_extended_lanes(
+            wave_function.array_mut(),
+            self.dimension_no,
+            &self.fft,
+            |lane, buffer| {
+                for (i, value) in lane.iter().enumerate() {
+                    buffer[i] = *value;
+                    if i > 0 && i < size - 1 {
+                        buffer[2 * (size - 1) - i] = *value;
+                    }
+                }
+            },
+            |buffer, mut lane| {
+                lane.iter_mut().zip(buffer.iter()).for_each(|(dest, src)| {
+                    *dest = *src * factor;
+                });
+            },
+        );
+    }
+}
+
+impl Transformation for DCTTransformation {
+    fn name(&self) -> &str {
+        "DCTTransformation"
+    }
+
+    #[inline(always)]
+    fn transform(&mut self, wave_function: &mut WaveFunction) {
+        wave_function.swap_grid(self.dimension_no, &mut self.grid_transformation);
+
+        self.cosine_transform(wave_function, self.transform_factor);
+    }
+
+    #[inline(always)]
+    fn inverse_transform(&mut self, wave_function: &mut WaveFunction) {
+        wave_function.swap_grid(self.dimension_no, &mut self.grid_transformation);
+
+        self.cosine_transform(wave_function, self.inverse_factor);
+    }
+
+    fn description(&self) -> OperationDescription {
+        OperationDescription {
+            dimensions: vec![self.dimension_no],
+            grids: vec![self.grid_transformation.name.clone()],
+            ..Default::default()
+        }
+    }
+}
@@ -1,13 +1,23 @@
 use std::{f64::consts::PI, sync::Arc};
 
-use crate::{grid::Grid, wave_function::WaveFunction};
+use crate::{grid::Grid, manifest::OperationDescription, wave_function::WaveFunction};
 
-use super::transformation::Transformation;
-use ndarray::{Axis, Zip};
+use super::{one_dim_propagator::OneDimPropagator, transformation::Transformation};
+use ndarray::{Array1, ArrayD, ArrayView1, ArrayViewMut1, Axis, Zip};
 use num::complex::Complex64;
-use rustfft::{Fft, FftPlanner};
+use rayon::prelude::*;
+use rustfft::{Fft, FftPlanner, Length};
+
+/// Number of elements of contiguous lanes transformed in a single parallel task.
+const CHUNK_ELEMENTS: usize = 1 << 14;
+
+/// Number of strided lanes copied together into a contiguous buffer.
+const STRIDED_BATCH: usize = 16;
 
 /// Diagonalization to operator eigenspace using Fourier transformation.
+///
+/// By default both transformations are normalized by `1/sqrt(N)`,
+/// the normalization can be folded into adjacent diagonal operator using [`FFTTransformation::fold_normalization_into`].
 #[derive(Clone)]
 pub struct FFTTransformation {
     dimension_no: usize,
@@ -15,6 +25,7 @@ pub struct FFTTransformation {
 
     fft: Box<Arc<dyn Fft<f64>>>,
     ifft: Box<Arc<dyn Fft<f64>>>,
+    normalized: bool,
 
     pub grid_transformation: Grid,
 }
@@ -25,65 +36,177 @@ impl FFTTransformation {
         let fft = FftPlanner::new().plan_fft_forward(grid.nodes_no);
         let ifft = FftPlanner::new().plan_fft_inverse(grid.nodes_no);
 
-        let momentum_step = 2.0 * PI / (grid.nodes.last().unwrap() - grid.nodes.first().unwrap()) * (1. - 1. / grid.nodes_no as f64);
-        let length: i64 = grid.nodes_no as i64;
-        let momenta: Vec<f64> = (0..length / 2)
-            .chain(-length / 2..0)
-            .map(|x| x as f64 * momentum_step)
-            .collect();
-
-        let mut weights: Vec<f64> = vec![momentum_step; momenta.len()];
-        weights[length as usize / 2 - 1] *= 0.5;
-        weights[length as usize / 2] *= 0.5;
-
-        let grid = Grid::new_custom(transformed_grid_name, momenta, weights, grid.dimension_no);
+        let grid = momentum_grid(grid, transformed_grid_name);
 
         FFTTransformation {
             dimension_no: grid.dimension_no,
             dimension_size: grid.nodes_no,
             fft: Box::new(fft),
             ifft: Box::new(ifft),
+            normalized: true,
             grid_transformation: grid,
         }
     }
+
+    /// Sets whether transformations are normalized by `1/sqrt(N)`.
+    pub fn set_normalized(&mut self, normalized: bool) {
+        self.normalized = normalized;
+    }
+
+    /// Returns the factor `1/N` that has to be applied once between unnormalized transformation and its inverse.
+    pub fn normalization_factor(&self) -> f64 {
+        1.0 / self.dimension_size as f64
+    }
+
+    /// Disables the normalization of the transformations and folds it into the `propagator`
+    /// acting on the transformed grid, saving two passes over the wave function per step.
+    /// The `propagator` has to be applied exactly once between the transformation and its inverse,
+    /// e.g. being the center of the operation stack, and the wave function in the transformed space is not normalized.
+    pub fn fold_normalization_into(&mut self, propagator: &mut OneDimPropagator) {
+        self.normalized = false;
+
+        let factor = Complex64::from(self.normalization_factor());
+        propagator.add_operator(Array1::from_elem(self.dimension_size, factor));
+    }
+
+    fn scaling(&self) -> f64 {
+        if self.normalized {
+            1.0 / (self.dimension_size as f64).sqrt()
+        } else {
+            1.0
+        }
+    }
 }
 
-impl Transformation for FFTTransformation {
-    #[inline(always)]
-    fn transform(&mut self, wave_function: &mut WaveFunction) {
-        wave_function.grids[self.dimension_no].swap(&mut self.grid_transformation);
-        wave_function.change_observer.possible_norm_change = true;
+/// Returns the momentum grid with name `transformed_grid_name` conjugate to the given `grid` in the FFT ordering.
+pub(crate) fn momentum_grid(grid: &Grid, transformed_grid_name: &str) -> Grid {
+    let momentum_step = 2.0 * PI / (grid.nodes.last().unwrap() - grid.nodes.first().unwrap()) * (1. - 1. / grid.nodes_no as f64);
+    let length: i64 = grid.nodes_no as i64;
+    let momenta: Vec<f64> = (0..length / 2)
+        .chain(-length / 2..0)
+        .map(|x| x as f64 * momentum_step)
+        .collect();
 
-        let dimension_size_sqrt = (self.dimension_size as f64).sqrt();
+    let mut weights: Vec<f64> = vec![momentum_step; momenta.len()];
+    weights[length as usize / 2 - 1] *= 0.5;
+    weights[length as usize / 2] *= 0.5;
 
-        Zip::from(wave_function.array.lanes_mut(Axis(self.dimension_no))).par_for_each(
-            |mut lane| {
-                let mut temp = lane.to_vec();
-                self.fft.process(&mut temp);
+    Grid::new_custom(transformed_grid_name, momenta, weights, grid.dimension_no)
+}
 
-                lane.iter_mut().zip(temp.iter()).for_each(|(dest, src)| {
-                    *dest = *src / dimension_size_sqrt;
-                });
+/// Applies `fft` along `axis` of the `array` and multiplies the result by `factor`.
+///
+/// Contiguous lanes are transformed in place in batches, strided lanes are copied in blocks
+/// into per-thread buffers and other memory layouts are transformed lane by lane.
+pub(crate) fn fft_along_axis(array: &mut ArrayD<Complex64>, axis: usize, fft: &Arc<dyn Fft<f64>>, factor: f64) {
+    let size = array.shape()[axis];
+    let scratch_len = fft.get_inplace_scratch_len();
+    let zero = Complex64::from(0.0);
+
+    if !array.is_standard_layout() {
+        Zip::from(array.lanes_mut(Axis(axis))).into_par_iter().for_each_init(
+            || (vec![zero; size], vec![zero; scratch_len]),
+            |(buffer, scratch), (mut lane,)| {
+                buffer.iter_mut().zip(lane.iter()).for_each(|(dest, src)| *dest = *src);
+                fft.process_with_scratch(buffer, scratch);
+                lane.iter_mut().zip(buffer.iter()).for_each(|(dest, src)| *dest = *src * factor);
             },
-        )
+        );
+
+        return;
     }
 
-    #[inline(always)]
-    fn inverse_transform(&mut self, wave_function: &mut WaveFunction) {
-        wave_function.grids[self.dimension_no].swap(&mut self.grid_transformation);
-        wave_function.change_observer.possible_norm_change = true;
+    let outer: usize = array.shape()[..axis].iter().product();
+    let inner: usize = array.shape()[axis + 1..].iter().product();
 
-        let dimension_size_sqrt = Complex64::from((self.dimension_size as f64).sqrt());
+    if inner == 1 {
+        let chunk_len = (CHUNK_ELEMENTS / size).max(1) * size;
 
-        Zip::from(wave_function.array.lanes_mut(Axis(self.dimension_no))).par_for_each(
-            |mut lane| {
-                let mut temp = lane.to_vec();
-                self.ifft.process(&mut temp);
+        array.as_slice_mut().unwrap().par_chunks_mut(chunk_len).for_each_init(
+            || vec![zero; scratch_len],
+            |scratch, chunk| {
+                fft.process_with_scratch(chunk, scratch);
+                if factor != 1.0 {
+                    chunk.iter_mut().for_each(|x| *x *= factor);
+                }
+            },
+        );
+
+        return;
+    }
 
-                lane.iter_mut().zip(temp.iter()).for_each(|(dest, src)| {
-                    *dest = *src / dimension_size_sqrt;
-                });
+    let mut view = array.view_mut().into_shape_with_order((outer, size, inner)).unwrap();
+    view.axis_iter_mut(Axis(0)).into_par_iter().for_each(|mut matrix| {
+        matrix.axis_chunks_iter_mut(Axis(1), STRIDED_BATCH).into_par_iter().for_each_init(
+            || (vec![zero; STRIDED_BATCH * size], vec![zero; scratch_len]),
+            |(buffer, scratch), mut block| {
+                let columns = block.shape()[1];
+                let buffer = &mut buffer[..columns * size];
+
+                for (i, row) in block.rows().into_iter().enumerate() {
+                    for (c, value) in row.iter().enumerate() {
+                        buffer[c * size + i] = *value;
+                    }
+                }
+                fft.process_with_scratch(buffer, scratch);
+                for (i, mut row) in block.rows_mut().into_iter().enumerate() {
+                    for (c, value) in row.iter_mut().enumerate() {
+                        *value = buffer[c * size + i] * factor;
+                    }
+                }
             },
-        )
+        );
+    });
+}
+
+/// Applies `fft` to the extension of each lane along `axis` of the `array` using per-thread buffers,
+/// e.g. odd or even extension of the lane for the sine and cosine transformations.
+///
+/// `extend` fills the whole buffer of length `fft.len()` from the lane
+/// and `restrict` sets the lane from the transformed buffer.
+pub(crate) fn fft_extended_lanes<E, R>(array: &mut ArrayD<Complex64>, axis: usize, fft: &Arc<dyn Fft<f64>>, extend: E, restrict: R)
+where
+    E: Fn(ArrayView1<Complex64>, &mut [Complex64]) + Sync,
+    R: Fn(&[Complex64], ArrayViewMut1<Complex64>) + Sync,
+{
+    let zero = Complex64::from(0.0);
+    let buffer_len = fft.len();
+    let scratch_len = fft.get_inplace_scratch_len();
+
+    Zip::from(array.lanes_mut(Axis(axis))).into_par_iter().for_each_init(
+        || (vec![zero; buffer_len], vec![zero; scratch_len]),
+        |(buffer, scratch), (lane,)| {
+            extend(lane.view(), buffer);
+            fft.process_with_scratch(buffer, scratch);
+            restrict(buffer, lane);
+        },
+    );
+}
+
+impl Transformation for FFTTransformation {
+    fn name(&self) -> &str {
+        "FFTTransformation"
+    }
+
+    #[inline(always)]
+    fn transform(&mut self, wave_function: &mut WaveFunction) {
+        wave_function.swap_grid(self.dimension_no, &mut self.grid_transformation);
+
+        fft_along_axis(wave_function.array_mut(), self.dimension_no, &self.fft, self.scaling());
+    }
+
+    #[inline(always)]
+    fn inverse_transform(&mut self, wave_function: &mut WaveFunction) {
+        wave_function.swap_grid(self.dimension_no, &mut self.grid_transformation);
+
+        fft_along_axis(wave_function.array_mut(), self.dimension_no, &self.ifft, self.scaling());
+    }
+
+    fn description(&self) -> OperationDescription {
+        OperationDescription {
+            dimensions: vec![self.dimension_no],
+            grids: vec![self.grid_transformation.name.clone()],
+            ..Default::default()
+        }
     }
 }
@@ -0,0 +1,319 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use ndarray::{Array1, ArrayViewMut1, Axis, Zip};
+use num::complex::Complex64;
+use quantum::particles::Particles;
+use rayon::prelude::*;
+use rustfft::{Fft, FftPlanner};
+
+use crate::{
+    grid::Grid,
+    loss_checker::LossChecker,
+    manifest::OperationDescription,
+    special_functions::{bessel_j_sequence, scaled_bessel_i_sequence},
+    time_grid::{select_step, TimeGrid, TimeStep},
+    wave_function::WaveFunction,
+};
+
+use super::Propagator;
+
+/// Relative magnitude below which the Chebyshev expansion of the propagator is truncated.
+const CHEBYSHEV_TOLERANCE: f64 = 1e-16;
+
+/// Propagator of the kinetic energy on the mapped grid created by [`Grid::new_mapped`],
+/// whose weights are the Jacobian J = dr/dx of the mapping.
+/// It acts in the uniform coordinate x reached by [`MappedTransformation`](super::mapped_transformation::MappedTransformation)
+/// on the transformed wave function phi = sqrt(J) psi.
+///
+/// The operator T = -1/(2m) J^(-1/2) d/dx J^(-1) d/dx J^(-1/2) is never stored as a matrix,
+/// each derivative in x is applied by FFT, multiplication by the wave numbers in momentum space and inverse FFT,
+/// with the Jacobian factors applied as diagonal operators before and after.
+/// Because of the Jacobian factors T is not diagonal in the momentum basis of x,
+/// so unlike the uniform kinetic term it cannot be propagated by a diagonal operator after [`FFTTransformation`](super::fft_transformation::FFTTransformation).
+/// Instead the exponential of T is expanded in Chebyshev polynomials of T, each term costing four FFTs per lane,
+/// with the number of terms growing with |dt| E_max, see `expansion_len`.
+/// The expansion is regenerated when the [`TimeGrid`] changes.
+#[derive(Clone)]
+pub struct MappedKineticPropagator {
+    dimension_no: usize,
+    fft: Arc<dyn Fft<f64>>,
+    ifft: Arc<dyn Fft<f64>>,
+
+    jacobian_sqrt: Array1<f64>,
+    inverse_jacobian: Array1<f64>,
+    derivative: Array1<Complex64>,
+    mass: f64,
+    max_energy: f64,
+
+    step: TimeStep,
+    coefficients: Vec<Complex64>,
+    loss_checked: Option<LossChecker>,
+}
+
+impl MappedKineticPropagator {
+    /// Creates new kinetic propagator in the uniform coordinate of the mapped `grid` with given [`TimeGrid`] and [`TimeStep`].
+    pub fn new(grid: &Grid, collision_params: &Particles, time_grid: &TimeGrid, step: TimeStep) -> Self {
+        let nodes_no = grid.nodes_no;
+        let mass = collision_params.red_mass();
+
+        let length = nodes_no as i64;
+        // Nyquist component is omitted so that the derivative is real and antisymmetric,
+        // normalization of the FFT is included in the wave numbers.
+        let derivative = (0..length)
+            .map(|n| if n <= (length - 1) / 2 { n } else { n - length })
+            .map(|n| {
+                if 2 * n == -length {
+                    Complex64::from(0.0)
+                } else {
+                    Complex64::new(0.0, 2.0 * PI * n as f64 / nodes_no as f64) / nodes_no as f64
+                }
+            })
+            .collect();
+
+        let inverse_jacobian: Array1<f64> = grid.weights.iter().map(|w| 1.0 / w).collect();
+        // bound of the spectrum of T given by the largest wave number pi of the unit step coordinate
+        let max_inverse_jacobian = inverse_jacobian.iter().fold(0.0f64, |acc, x| acc.max(*x));
+        let max_energy = PI * PI * max_inverse_jacobian * max_inverse_jacobian / (2.0 * mass);
+
+        let mut propagator = MappedKineticPropagator {
+            dimension_no: grid.dimension_no,
+            fft: FftPlanner::new().plan_fft_forward(nodes_no),
+            ifft: FftPlanner::new().plan_fft_inverse(nodes_no),
+            jacobian_sqrt: grid.weights.iter().map(|w| w.sqrt()).collect(),
+            inverse_jacobian,
+            derivative,
+            mass,
+            max_energy,
+            step,
+            coefficients: Vec::new(),
+            loss_checked: None,
+        };
+        propagator.coefficients = propagator.chebyshev_coefficients(time_grid);
+
+        propagator
+    }
+
+    pub fn set_loss_checked(&mut self, loss_checked: LossChecker) {
+        self.loss_checked = Some(loss_checked);
+    }
+
+    /// Returns the bound of the kinetic energies used in the Chebyshev expansion.
+    pub fn max_energy(&self) -> f64 {
+        self.max_energy
+    }
+
+    /// Returns the number of terms of the Chebyshev expansion, each costing four FFTs per lane.
+    pub fn expansion_len(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// Multiplies the wave function in the uniform coordinate of the mapping by the kinetic energy operator.
+    pub fn kinetic_action(&self, wave_function: &mut WaveFunction) {
+        self.for_each_lane(wave_function, |propagator, mut lane, buffers| {
+            buffers.phi.iter_mut().zip(lane.iter()).for_each(|(dest, x)| *dest = *x);
+            propagator.kinetic(&buffers.phi, &mut buffers.current, &mut buffers.scratch);
+            lane.iter_mut().zip(buffers.current.iter()).for_each(|(dest, x)| *dest = *x);
+        });
+    }
+
+    /// Returns copy of the propagator with given [`TimeStep`] regenerated for given [`TimeGrid`].
+    pub(crate) fn with_step(&self, step: TimeStep, time_grid: &TimeGrid) -> Self {
+        let mut propagator = self.clone();
+        propagator.step = step;
+        propagator.coefficients = propagator.chebyshev_coefficients(time_grid);
+
+        propagator
+    }
+
+    /// Returns Chebyshev coefficients of exp(-i T dt) in the polynomials of X = 2 T / E - 1,
+    /// where E is the bound of the kinetic energies.
+    fn chebyshev_coefficients(&self, time_grid: &TimeGrid) -> Vec<Complex64> {
+        let dt = select_step(self.step, time_grid);
+        let half_width = self.max_energy / 2.0;
+
+        let coefficients: Vec<Complex64> = if dt.im == 0.0 {
+            // exp(-i w (X + 1)) = exp(-i w) sum (2 - delta_k0) (-i)^k J_k(w) T_k(X)
+            let w = dt.re * half_width;
+            let phase = Complex64::new(0.0, -w).exp();
+
+            bessel_j_sequence(expansion_order(w.abs()), w)
+                .into_iter()
+                .enumerate()
+                .map(|(k, bessel)| phase * Complex64::new(0.0, -1.0).powu(k as u32) * bessel)
+                .collect()
+        } else {
+            // exp(-a (X + 1)) = sum (2 - delta_k0) (-1)^k exp(-a) I_k(a) T_k(X)
+            let a = -dt.im * half_width;
+
+            scaled_bessel_i_sequence(expansion_order(a), a)
+                .into_iter()
+                .enumerate()
+                .map(|(k, bessel)| Complex64::from(if k.is_multiple_of(2) { bessel } else { -bessel }))
+                .collect()
+        };
+
+        let len = coefficients.iter()
+            .rposition(|c| c.norm() > CHEBYSHEV_TOLERANCE)
+            .map_or(1, |last| last + 1);
+
+        coefficients
+            .into_iter()
+            .take(len)
+            .enumerate()
+            .map(|(k, c)| if k == 0 { c } else { 2.0 * c })
+            .collect()
+    }
+
+    /// Calls `action` on each lane along the grid dimension with per thread buffers.
+    fn for_each_lane<F>(&self, wave_function: &mut WaveFunction, action: F)
+    where
+        F: Fn(&Self, ArrayViewMut1<Complex64>, &mut LaneBuffers) + Sync,
+    {
+        let size = self.jacobian_sqrt.len();
+        let scratch_len = self.fft.get_inplace_scratch_len().max(self.ifft.get_inplace_scratch_len());
+
+        Zip::from(wave_function.array_mut().lanes_mut(Axis(self.dimension_no))).into_par_iter().for_each_init(
+            || LaneBuffers::new(size, scratch_len),
+            |buffers, (lane,)| action(self, lane, buffers),
+        );
+    }
+
+    /// Applies the derivative in the uniform coordinate to `buffer` using FFT.
+    fn derivative(&self, buffer: &mut [Complex64], scratch: &mut [Complex64]) {
+        self.fft.process_with_scratch(buffer, scratch);
+        buffer.iter_mut().zip(self.derivative.iter()).for_each(|(x, k)| *x *= k);
+        self.ifft.process_with_scratch(buffer, scratch);
+    }
+
+    /// Writes T `phi` into `result`.
+    fn kinetic(&self, phi: &[Complex64], result: &mut [Complex64], scratch: &mut [Complex64]) {
+        result.iter_mut()
+            .zip(phi.iter().zip(self.jacobian_sqrt.iter()))
+            .for_each(|(dest, (x, j))| *dest = x / j);
+
+        self.derivative(result, scratch);
+        result.iter_mut().zip(self.inverse_jacobian.iter()).for_each(|(x, j)| *x *= j);
+        self.derivative(result, scratch);
+
+        let factor = -1.0 / (2.0 * self.mass);
+        result.iter_mut()
+            .zip(self.jacobian_sqrt.iter())
+            .for_each(|(x, j)| *x *= factor / j);
+    }
+
+    /// Writes X `phi` = (2 T / E - 1) `phi` into `result`.
+    fn scaled_kinetic(&self, phi: &[Complex64], result: &mut [Complex64], scratch: &mut [Complex64]) {
+        self.kinetic(phi, result, scratch);
+
+        let factor = 2.0 / self.max_energy;
+        result.iter_mut().zip(phi.iter()).for_each(|(x, p)| *x = *x * factor - p);
+    }
+
+    /// Replaces `phi` of the `buffers` by exp(-i T dt) `phi` using the Chebyshev recurrence T_(k+1) = 2 X T_k - T_(k-1).
+    fn exponential(&self, buffers: &mut LaneBuffers) {
+        let LaneBuffers { phi, previous, current, next, scratch } = buffers;
+
+        previous.copy_from_slice(phi);
+        phi.iter_mut().for_each(|x| *x *= self.coefficients[0]);
+        if self.coefficients.len() == 1 {
+            return;
+        }
+
+        self.scaled_kinetic(previous, current, scratch);
+        phi.iter_mut().zip(current.iter()).for_each(|(x, t)| *x += self.coefficients[1] * t);
+
+        for c in &self.coefficients[2..] {
+            self.scaled_kinetic(current, next, scratch);
+            next.iter_mut().zip(previous.iter()).for_each(|(x, p)| *x = 2.0 * *x - p);
+            phi.iter_mut().zip(next.iter()).for_each(|(x, t)| *x += c * t);
+
+            std::mem::swap(previous, current);
+            std::mem::swap(current, next);
+        }
+    }
+
+    fn apply_unchecked(&self, wave_function: &mut WaveFunction) {
+        self.for_each_lane(wave_function, |propagator, mut lane, buffers| {
+            buffers.phi.iter_mut().zip(lane.iter()).for_each(|(dest, x)| *dest = *x);
+            propagator.exponential(buffers);
+            lane.iter_mut().zip(buffers.phi.iter()).for_each(|(dest, x)| *dest = *x);
+        });
+    }
+}
+
+/// Buffers of a single lane used by the Chebyshev recurrence.
+struct LaneBuffers {
+    phi: Vec<Complex64>,
+    previous: Vec<Complex64>,
+    current: Vec<Complex64>,
+    next: Vec<Complex64>,
+    scratch: Vec<Complex64>,
+}
+
+impl LaneBuffers {
+    fn new(size: usize, scratch_len: usize) -> Self {
+        let zero = Complex64::from(0.0);
+
+        LaneBuffers {
+            phi: vec![zero; size],
+            previous: vec![zero; size],
+            current: vec![zero; size],
+            next: vec![zero; size],
+            scratch: vec![zero; scratch_len],
+        }
+    }
+}
+
+/// Returns the order of Chebyshev expansion sufficient for the exponential with argument of magnitude `a`.
+fn expansion_order(a: f64) -> usize {
+    (a + 10.0 * a.cbrt()) as usize + 30
+}
+
+impl Propagator for MappedKineticPropagator {
+    fn name(&self) -> &str {
+        "MappedKineticPropagator"
+    }
+
+    fn apply(&mut self, wave_function: &mut WaveFunction, time: f64) {
+        if let Some(loss_checker) = &mut self.loss_checked {
+            loss_checker.check_before(wave_function);
+        }
+
+        self.apply_unchecked(wave_function);
+
+        if let Some(loss_checker) = &mut self.loss_checked {
+            loss_checker.check_after(wave_function, time);
+        }
+    }
+
+    fn loss(&self) -> &Option<LossChecker> {
+        &self.loss_checked
+    }
+
+    fn loss_mut(&mut self) -> &mut Option<LossChecker> {
+        &mut self.loss_checked
+    }
+
+    fn loss_reset(&mut self) {
+        if let Some(loss_checker) = &mut self.loss_checked {
+            loss_checker.reset();
+        }
+    }
+
+    fn follows_time_grid(&self) -> bool {
+        true
+    }
+
+    fn update_time_grid(&mut self, _previous: &TimeGrid, time_grid: &TimeGrid) {
+        self.coefficients = self.chebyshev_coefficients(time_grid);
+    }
+
+    fn description(&self) -> OperationDescription {
+        OperationDescription {
+            step: Some(format!("{:?}", self.step)),
+            dimensions: vec![self.dimension_no],
+            generator: Some("MappedKinetic".to_string()),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use ndarray::{Array1, Axis, Zip};
+
+use crate::{grid::Grid, manifest::OperationDescription, wave_function::WaveFunction};
+
+use super::transformation::Transformation;
+
+/// Transformation from the mapped grid created by [`Grid::new_mapped`] to the uniform coordinate x of the mapping,
+/// in which the kinetic energy is applied by [`MappedKineticPropagator`](super::mapped_kinetic_propagator::MappedKineticPropagator).
+///
+/// The wave function psi(r) is transformed to phi(x) = sqrt(J) psi, where J = dr/dx are the weights of the mapped grid.
+/// Transformed grid contains nodes x = 0, 1, ..., N - 1 with unit weights, such that the norm of the wave function is preserved.
+#[derive(Clone)]
+pub struct MappedTransformation {
+    dimension_no: usize,
+    jacobian_sqrt: Array1<f64>,
+
+    pub grid_transformation: Grid,
+}
+
+impl MappedTransformation {
+    /// Creates new [`MappedTransformation`] along given mapped grid that transforms this grid into new grid with name `transformed_grid_name`.
+    pub fn new(grid: &Grid, transformed_grid_name: &str) -> Self {
+        assert!(grid.weights.iter().all(|&w| w > 0.0), "Jacobian of the mapping has to be positive");
+
+        let nodes_no = grid.nodes_no;
+
+        MappedTransformation {
+            dimension_no: grid.dimension_no,
+            jacobian_sqrt: grid.weights.iter().map(|w| w.sqrt()).collect(),
+            grid_transformation: Grid::new_linear_countable(
+                transformed_grid_name,
+                0.0,
+                nodes_no as f64 - 1.0,
+                nodes_no,
+                grid.dimension_no,
+            ),
+        }
+    }
+}
+
+impl Transformation for MappedTransformation {
+    fn name(&self) -> &str {
+        "MappedTransformation"
+    }
+
+    #[inline(always)]
+    fn transform(&mut self, wave_function: &mut WaveFunction) {
+        wave_function.swap_grid(self.dimension_no, &mut self.grid_transformation);
+
+        Zip::from(wave_function.array_mut().lanes_mut(Axis(self.dimension_no)))
+            .par_for_each(|mut lane| lane.iter_mut().zip(self.jacobian_sqrt.iter()).for_each(|(x, j)| *x *= *j));
+    }
+
+    #[inline(always)]
+    fn inverse_transform(&mut self, wave_function: &mut WaveFunction) {
+        wave_function.swap_grid(self.dimension_no, &mut self.grid_transformation);
+
+        Zip::from(wave_function.array_mut().lanes_mut(Axis(self.dimension_no)))
+            .par_for_each(|mut lane| lane.iter_mut().zip(self.jacobian_sqrt.iter()).for_each(|(x, j)| *x /= *j));
+    }
+
+    fn description(&self) -> OperationDescription {
+        OperationDescription {
+            dimensions: vec![self.dimension_no],
+            grids: vec![self.grid_transformation.name.clone()],
+            ..Default::default()
+        }
+    }
+}
@@ -1,10 +1,12 @@
-use crate::{grid::Grid, wave_function::WaveFunction};
+use crate::{grid::Grid, manifest::OperationDescription, wave_function::WaveFunction};
 
 use super::transformation::Transformation;
-use ndarray::{Array2, Axis, Zip};
+use ndarray::{linalg::general_mat_mul, Array2, ArrayD, ArrayView3, ArrayViewD, ArrayViewMut3, ArrayViewMutD, Axis, Zip};
 use num::complex::Complex64;
+use rayon::prelude::*;
 
 /// Diagonalization to operator eigenspace using matrix transformation.
+/// The transformation is performed as matrix-matrix products into a buffer reused between steps.
 #[derive(Clone)]
 pub struct MatrixTransformation {
     dimension_no: usize,
@@ -12,6 +14,7 @@ pub struct MatrixTransformation {
 
     transformation: Array2<Complex64>,
     inverse_transformation: Array2<Complex64>,
+    buffer: ArrayD<Complex64>,
 
     pub grid_transformation: Grid,
 }
@@ -28,6 +31,7 @@ impl MatrixTransformation {
             dimension_size: grid.nodes_no,
             transformation: Array2::zeros((grid.nodes_no, grid.nodes_no)),
             inverse_transformation: Array2::zeros((grid.nodes_no, grid.nodes_no)),
+            buffer: ArrayD::zeros(vec![0]),
             grid_transformation,
         }
     }
@@ -56,21 +60,122 @@ impl MatrixTransformation {
 }
 
 impl Transformation for MatrixTransformation {
+    fn name(&self) -> &str {
+        "MatrixTransformation"
+    }
+
     #[inline(always)]
     fn transform(&mut self, wave_function: &mut WaveFunction) {
-        wave_function.grids[self.dimension_no].swap(&mut self.grid_transformation);
-        wave_function.change_observer.possible_norm_change = true;
+        wave_function.swap_grid(self.dimension_no, &mut self.grid_transformation);
 
-        Zip::from(wave_function.array.lanes_mut(Axis(self.dimension_no)))
-            .par_for_each(|mut lane| lane.assign(&self.transformation.dot(&lane)));
+        prepare_buffer(wave_function.array_mut(), &mut self.buffer);
+        matrix_along_axis(&self.transformation, wave_function.array(), &mut self.buffer, self.dimension_no);
+        std::mem::swap(wave_function.array_mut(), &mut self.buffer);
     }
 
     #[inline(always)]
     fn inverse_transform(&mut self, wave_function: &mut WaveFunction) {
-        wave_function.grids[self.dimension_no].swap(&mut self.grid_transformation);
-        wave_function.change_observer.possible_norm_change = true;
+        wave_function.swap_grid(self.dimension_no, &mut self.grid_transformation);
+
+        prepare_buffer(wave_function.array_mut(), &mut self.buffer);
+        matrix_along_axis(&self.inverse_transformation, wave_function.array(), &mut self.buffer, self.dimension_no);
+        std::mem::swap(wave_function.array_mut(), &mut self.buffer);
+    }
+
+    fn description(&self) -> OperationDescription {
+        OperationDescription {
+            dimensions: vec![self.dimension_no],
+            grids: vec![self.grid_transformation.name.clone()],
+            ..Default::default()
+        }
+    }
+}
+
+/// Converts `array` to standard memory layout if needed
+/// and reallocates `buffer` only if its shape differs from the shape of the `array`.
+pub(crate) fn prepare_buffer(array: &mut ArrayD<Complex64>, buffer: &mut ArrayD<Complex64>) {
+    if !array.is_standard_layout() {
+        *array = array.as_standard_layout().into_owned();
+    }
+
+    if buffer.shape() != array.shape() || !buffer.is_standard_layout() {
+        *buffer = ArrayD::zeros(array.raw_dim());
+    }
+}
 
-        Zip::from(wave_function.array.lanes_mut(Axis(self.dimension_no)))
-            .par_for_each(|mut lane| lane.assign(&self.inverse_transformation.dot(&lane)));
+/// Writes `matrix` applied along `axis` of `input` into `output`, both in standard layout,
+/// using a single matrix-matrix product per block of lanes.
+pub(crate) fn matrix_along_axis(
+    matrix: &Array2<Complex64>,
+    input: &ArrayD<Complex64>,
+    output: &mut ArrayD<Complex64>,
+    axis: usize,
+) {
+    let size = input.shape()[axis];
+    let outer: usize = input.shape()[..axis].iter().product();
+    let inner: usize = input.shape()[axis + 1..].iter().product();
+
+    let input = input.view().into_shape_with_order((outer, size, inner)).unwrap();
+    let output = output.view_mut().into_shape_with_order((outer, size, inner)).unwrap();
+
+    matrix_along_middle_axis(matrix, input, output);
+}
+
+/// Writes `matrix` applied along `axis` of arbitrarily strided `input` into `output`.
+pub(crate) fn matrix_along_view_axis(
+    matrix: &Array2<Complex64>,
+    mut input: ArrayViewD<Complex64>,
+    mut output: ArrayViewMutD<Complex64>,
+    mut axis: usize,
+) {
+    if axis == 0 {
+        input.insert_axis_inplace(Axis(0));
+        output.insert_axis_inplace(Axis(0));
+        axis += 1;
+    }
+    if axis == input.ndim() - 1 {
+        input.insert_axis_inplace(Axis(input.ndim()));
+        output.insert_axis_inplace(Axis(output.ndim()));
+    }
+
+    if input.ndim() == 3 {
+        let input = input.into_dimensionality().unwrap();
+        let output = output.into_dimensionality().unwrap();
+
+        matrix_along_middle_axis(matrix, input, output);
+    } else if axis > 1 {
+        input.axis_iter(Axis(0))
+            .into_par_iter()
+            .zip(output.axis_iter_mut(Axis(0)))
+            .for_each(|(input, output)| matrix_along_view_axis(matrix, input, output, axis - 1));
+    } else {
+        let last = input.ndim() - 1;
+        input.axis_iter(Axis(last))
+            .into_par_iter()
+            .zip(output.axis_iter_mut(Axis(last)))
+            .for_each(|(input, output)| matrix_along_view_axis(matrix, input, output, axis));
+    }
+}
+
+/// Writes `matrix` applied along the middle axis of `input` with shape (outer, N, inner) into `output`.
+fn matrix_along_middle_axis(matrix: &Array2<Complex64>, input: ArrayView3<Complex64>, mut output: ArrayViewMut3<Complex64>) {
+    let one = Complex64::from(1.0);
+    let zero = Complex64::from(0.0);
+
+    if input.shape()[2] == 1 {
+        // lanes are rows of (outer, N) matrix, so that the result is X T^T
+        general_mat_mul(
+            one,
+            &input.index_axis(Axis(2), 0),
+            &matrix.t(),
+            zero,
+            &mut output.index_axis_mut(Axis(2), 0),
+        );
+    } else if input.shape()[0] == 1 {
+        general_mat_mul(one, matrix, &input.index_axis(Axis(0), 0), zero, &mut output.index_axis_mut(Axis(0), 0));
+    } else {
+        Zip::from(input.axis_iter(Axis(0)))
+            .and(output.axis_iter_mut(Axis(0)))
+            .par_for_each(|input, mut output| general_mat_mul(one, matrix, &input, zero, &mut output));
     }
 }
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use crate::{grid::Grid, manifest::OperationDescription, wave_function::WaveFunction};
+
+use super::{
+    fft_transformation::{fft_along_axis, momentum_grid},
+    n_dim_propagator::NDimPropagator,
+    transformation::Transformation,
+};
+use num::complex::Complex64;
+use rustfft::{Fft, FftPlanner};
+
+/// Diagonalization to operator eigenspace using Fourier transformation over several axes at once.
+/// All transformed grids are swapped together to their momentum grids.
+///
+/// Axes are transformed starting from the last one, which has the most contiguous lanes.
+/// By default the transformations are normalized by `1/sqrt(N)` for each axis,
+/// the normalization can be folded into adjacent diagonal operator using [`MultiFFTTransformation::fold_normalization_into`].
+#[derive(Clone)]
+pub struct MultiFFTTransformation {
+    dimensions: Vec<usize>,
+    dimension_sizes: Vec<usize>,
+
+    ffts: Vec<Arc<dyn Fft<f64>>>,
+    iffts: Vec<Arc<dyn Fft<f64>>>,
+    normalized: bool,
+
+    /// Momentum grids sorted by the dimension number in descending order.
+    pub grid_transformations: Vec<Grid>,
+}
+
+impl MultiFFTTransformation {
+    /// Creates new [`MultiFFTTransformation`] along given grids that transforms them
+    /// into new grids with names `transformed_grid_names` given in the same order.
+    pub fn new(grids: &[&Grid], transformed_grid_names: &[&str]) -> Self {
+        assert!(
+            grids.len() == transformed_grid_names.len(),
+            "Number of grids and transformed grid names have to be equal"
+        );
+
+        let mut order: Vec<usize> = (0..grids.len()).collect();
+        order.sort_by(|&a, &b| grids[b].dimension_no.cmp(&grids[a].dimension_no));
+        assert!(
+            order.windows(2).all(|x| grids[x[0]].dimension_no != grids[x[1]].dimension_no),
+            "Grids have to act on different dimensions"
+        );
+
+        let mut planner = FftPlanner::new();
+
+        MultiFFTTransformation {
+            dimensions: order.iter().map(|&i| grids[i].dimension_no).collect(),
+            dimension_sizes: order.iter().map(|&i| grids[i].nodes_no).collect(),
+            ffts: order.iter().map(|&i| planner.plan_fft_forward(grids[i].nodes_no)).collect(),
+            iffts: order.iter().map(|&i| planner.plan_fft_inverse(grids[i].nodes_no)).collect(),
+            normalized: true,
+            grid_transformations: order
+                .iter()
+                .map(|&i| momentum_grid(grids[i], transformed_grid_names[i]))
+                .collect(),
+        }
+    }
+
+    /// Sets whether transformations are normalized by `1/sqrt(N)` for each axis.
+    pub fn set_normalized(&mut self, normalized: bool) {
+        self.normalized = normalized;
+    }
+
+    /// Returns the factor `1/(N_1 N_2 ...)` that has to be applied once between unnormalized transformation and its inverse.
+    pub fn normalization_factor(&self) -> f64 {
+        1.0 / self.dimension_sizes.iter().product::<usize>() as f64
+    }
+
+    /// Disables the normalization of the transformations and folds it into the `propagator`
+    /// acting on the transformed grids, see [`FFTTransformation::fold_normalization_into`](super::fft_transformation::FFTTransformation::fold_normalization_into).
+    pub fn fold_normalization_into(&mut self, propagator: &mut NDimPropagator) {
+        self.normalized = false;
+
+        propagator.scale(Complex64::from(self.normalization_factor()));
+    }
+
+    fn swap_grids(&mut self, wave_function: &mut WaveFunction) {
+        for (dimension, grid) in self.dimensions.iter().zip(self.grid_transformations.iter_mut()) {
+            wave_function.swap_grid(*dimension, grid);
+        }
+    }
+
+    fn scaling(&self) -> f64 {
+        if self.normalized {
+            self.normalization_factor().sqrt()
+        } else {
+            1.0
+        }
+    }
+}
+
+impl Transformation for MultiFFTTransformation {
+    fn name(&self) -> &str {
+        "MultiFFTTransformation"
+    }
+
+    #[inline(always)]
+    fn transform(&mut self, wave_function: &mut WaveFunction) {
+        self.swap_grids(wave_function);
+
+        let last = self.dimensions.len() - 1;
+        for (i, (dimension, fft)) in self.dimensions.iter().zip(self.ffts.iter()).enumerate() {
+            let factor = if i == last { self.scaling() } else { 1.0 };
+            fft_along_axis(wave_function.array_mut(), *dimension, fft, factor);
+        }
+    }
+
+    #[inline(always)]
+    fn inverse_transform(&mut self, wave_function: &mut WaveFunction) {
+        self.swap_grids(wave_function);
+
+        let last = self.dimensions.len() - 1;
+        for (i, (dimension, ifft)) in self.dimensions.iter().zip(self.iffts.iter()).enumerate() {
+            let factor = if i == last { self.scaling() } else { 1.0 };
+            fft_along_axis(wave_function.array_mut(), *dimension, ifft, factor);
+        }
+    }
+
+    fn description(&self) -> OperationDescription {
+        OperationDescription {
+            dimensions: self.dimensions.clone(),
+            grids: self.grid_transformations.iter().map(|grid| grid.name.clone()).collect(),
+            ..Default::default()
+        }
+    }
+}
@@ -1,13 +1,18 @@
 use ndarray::{ArrayD, IxDyn};
 use num::complex::Complex64;
 
-use crate::{loss_checker::LossChecker, wave_function::WaveFunction};
+use crate::{loss_checker::LossChecker, manifest::OperationDescription, time_grid::TimeGrid, wave_function::WaveFunction};
 
-use super::Propagator;
+use super::{operator_generator::OperatorGenerator, Propagator};
 
+/// Propagator acting with diagonal operator on the whole wave function array.
+/// If created from a hamiltonian, the operator is regenerated when the [`TimeGrid`] changes,
+/// keeping the factors added by `add_operator` and `scale`.
 #[derive(Clone)]
 pub struct NDimPropagator {
     operator: ArrayD<Complex64>,
+    generator: Option<OperatorGenerator<IxDyn>>,
+    factors: Option<ArrayD<Complex64>>,
     loss_checked: Option<LossChecker>,
 }
 
@@ -15,24 +20,45 @@ impl NDimPropagator {
     pub fn new() -> NDimPropagator {
         NDimPropagator {
             operator: ArrayD::zeros(IxDyn(&[1])),
+            generator: None,
+            factors: None,
             loss_checked: None,
         }
     }
 
     pub fn set_operator(&mut self, operator: ArrayD<Complex64>) {
         self.operator = operator;
+        self.generator = None;
+        self.factors = None;
+    }
+
+    /// Sets the operator generated by `generator` for given [`TimeGrid`].
+    pub(crate) fn set_generator(&mut self, generator: OperatorGenerator<IxDyn>, time_grid: &TimeGrid) {
+        self.set_operator(generator.operator(time_grid));
+        self.generator = Some(generator);
     }
 
     pub fn add_operator(&mut self, operator: ArrayD<Complex64>) {
         assert!(operator.shape() == self.operator.shape());
 
         self.operator *= &operator;
+        match &mut self.factors {
+            Some(factors) => *factors *= &operator,
+            None => self.factors = Some(operator),
+        }
     }
 
-    fn apply_unchecked(&self, wave_function: &mut WaveFunction) {
-        wave_function.change_observer.possible_norm_change = true;
+    /// Multiplies the operator by constant `factor`.
+    pub fn scale(&mut self, factor: Complex64) {
+        self.operator *= factor;
+        match &mut self.factors {
+            Some(factors) => *factors *= factor,
+            None => self.factors = Some(ArrayD::from_elem(self.operator.raw_dim(), factor)),
+        }
+    }
 
-        wave_function.array *= &self.operator;
+    fn apply_unchecked(&self, wave_function: &mut WaveFunction) {
+        *wave_function.array_mut() *= &self.operator;
     }
 
     pub fn set_loss_checked(&mut self, loss_checked: LossChecker) {
@@ -41,7 +67,11 @@ impl NDimPropagator {
 }
 
 impl Propagator for NDimPropagator {
-    fn apply(&mut self, wave_function: &mut WaveFunction) {
+    fn name(&self) -> &str {
+        "NDimPropagator"
+    }
+
+    fn apply(&mut self, wave_function: &mut WaveFunction, time: f64) {
         if let Some(loss_checker) = &mut self.loss_checked {
             loss_checker.check_before(wave_function);
         }
@@ -49,7 +79,7 @@ impl Propagator for NDimPropagator {
         self.apply_unchecked(wave_function);
 
         if let Some(loss_checker) = &mut self.loss_checked {
-            loss_checker.check_after(wave_function);
+            loss_checker.check_after(wave_function, time);
         }
     }
 
@@ -57,9 +87,33 @@ impl Propagator for NDimPropagator {
         &self.loss_checked
     }
 
+    fn loss_mut(&mut self) -> &mut Option<LossChecker> {
+        &mut self.loss_checked
+    }
+
     fn loss_reset(&mut self) {
         if let Some(loss_checker) = &mut self.loss_checked {
             loss_checker.reset();
         }
     }
+
+    fn follows_time_grid(&self) -> bool {
+        self.generator.is_some()
+    }
+
+    fn update_time_grid(&mut self, _previous: &TimeGrid, time_grid: &TimeGrid) {
+        if let Some(generator) = &self.generator {
+            self.operator = generator.operator(time_grid);
+            if let Some(factors) = &self.factors {
+                self.operator *= factors;
+            }
+        }
+    }
+
+    fn description(&self) -> OperationDescription {
+        match &self.generator {
+            Some(generator) => generator.description(Vec::new()),
+            None => OperationDescription::default(),
+        }
+    }
 }
@@ -1,14 +1,35 @@
-use ndarray::{ Array2, Axis };
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use ndarray::{linalg::general_mat_vec_mul, Array1, Array2, ArrayD, ArrayView, ArrayViewD, Axis, IxDyn, RemoveAxis, Zip};
 use num::complex::Complex64;
 use rayon::prelude::*;
 
-use crate::{loss_checker::LossChecker, wave_function::WaveFunction};
+use crate::{loss_checker::LossChecker, manifest::OperationDescription, time_grid::TimeGrid, wave_function::WaveFunction};
+
+use super::{operator_generator::MatrixGenerator, Propagator};
 
-use super::Propagator;
+/// Assignment of the operators to the lanes of the channel axis.
+#[derive(Clone)]
+enum OperatorIndexing {
+    /// Operator index for each lane in the row-major order of the remaining axes.
+    Lanes(Vec<usize>),
+    /// Operator index for each lane with the remaining axes in order,
+    /// axes other than the index `positions` have length 1 and the operators are shared along them.
+    Indexed { positions: Vec<usize>, lane_indices: ArrayD<usize> },
+}
 
+/// Propagator coupling the states of the channel axis `dimension_no` with matrices
+/// that depend on the grid points of other axes, e.g. potential coupling matrix for each radial point.
+/// Repeating matrices are stored once and referenced by index.
+/// If created from channel hamiltonians, the operators are regenerated when the [`TimeGrid`] changes.
 #[derive(Clone)]
 pub struct NonDiagPropagator {
     operators: Vec<Array2<Complex64>>,
+    indexing: OperatorIndexing,
+    generator: Option<MatrixGenerator>,
     dimension_no: usize,
     loss_checked: Option<LossChecker>,
 }
@@ -17,23 +38,135 @@ impl NonDiagPropagator {
     pub fn new(dimension_no: usize) -> Self {
         Self {
             operators: Vec::new(),
+            indexing: OperatorIndexing::Lanes(Vec::new()),
+            generator: None,
             dimension_no: dimension_no,
             loss_checked: None,
         }
     }
 
+    /// Sets operators for each lane of the channel axis in the row-major order of the remaining axes.
+    /// Repeating operators are stored once.
     pub fn set_operators(&mut self, operators: Vec<Array2<Complex64>>) {
+        let (operators, lane_indices) = deduplicate(operators);
+        self.check_operators(&operators);
+
         self.operators = operators;
+        self.indexing = OperatorIndexing::Lanes(lane_indices);
+        self.generator = None;
     }
 
-    fn apply_unchecked(&self, wave_function: &mut WaveFunction) {
-        wave_function.change_observer.possible_norm_change = true;
+    /// Sets `operators` indexed by the grid points of `index_axes`,
+    /// such that `indices[[i_1, i_2, ...]]` is the index of the operator used at the grid point (i_1, i_2, ...) of `index_axes`.
+    /// Operators are shared along the axes that are not in `index_axes`. Repeating operators are stored once.
+    pub fn set_indexed_operators(&mut self, operators: Vec<Array2<Complex64>>, index_axes: Vec<usize>, indices: ArrayD<usize>) {
+        assert!(indices.iter().all(|&i| i < operators.len()), "Operator index out of bounds");
+        let (operators, operator_indices) = deduplicate(operators);
+        let indices = indices.mapv(|i| operator_indices[i]);
+
+        self.store_indexed_operators(operators, index_axes, indices);
+        self.generator = None;
+    }
+
+    /// Sets indexed operators generated by `generator` for given [`TimeGrid`], see [`NonDiagPropagator::set_indexed_operators`].
+    /// Generated operators are stored as they are, so that they can be replaced when the [`TimeGrid`] changes.
+    pub(crate) fn set_generator(
+        &mut self,
+        generator: MatrixGenerator,
+        index_axes: Vec<usize>,
+        indices: ArrayD<usize>,
+        time_grid: &TimeGrid,
+    ) {
+        self.store_indexed_operators(generator.operators(time_grid), index_axes, indices);
+        self.generator = Some(generator);
+    }
+
+    fn store_indexed_operators(&mut self, operators: Vec<Array2<Complex64>>, index_axes: Vec<usize>, indices: ArrayD<usize>) {
+        self.check_operators(&operators);
+        assert!(indices.ndim() == index_axes.len(), "Indices have to have dimension for each index axis");
+        assert!(!index_axes.contains(&self.dimension_no), "Index axes cannot contain the channel axis");
+        for (i, axis) in index_axes.iter().enumerate() {
+            assert!(!index_axes[i + 1..].contains(axis), "Index axes have to be unique");
+        }
+        assert!(indices.iter().all(|&i| i < operators.len()), "Operator index out of bounds");
+
+        // position of the index axes among the lanes axes, that are all axes except the channel axis
+        let positions: Vec<usize> = index_axes.iter()
+            .map(|&axis| if axis < self.dimension_no { axis } else { axis - 1 })
+            .collect();
+        let mut order: Vec<usize> = (0..index_axes.len()).collect();
+        order.sort_by_key(|&i| positions[i]);
+
+        let mut lane_shape = vec![1; positions.iter().max().map_or(0, |&p| p + 1)];
+        for (&position, &size) in positions.iter().zip(indices.shape()) {
+            lane_shape[position] = size;
+        }
+        let lane_indices = indices
+            .permuted_axes(order)
+            .as_standard_layout()
+            .into_owned()
+            .into_shape_with_order(IxDyn(&lane_shape))
+            .unwrap();
+
+        self.operators = operators;
+        self.indexing = OperatorIndexing::Indexed { positions, lane_indices };
+    }
+
+    /// Returns the number of stored distinct operators.
+    pub fn operators_no(&self) -> usize {
+        self.operators.len()
+    }
+
+    fn check_operators(&self, operators: &[Array2<Complex64>]) {
+        if let Some(first) = operators.first() {
+            let size = first.shape()[0];
+            assert!(
+                operators.iter().all(|op| op.shape() == [size, size]),
+                "Operators have to be square matrices of equal size"
+            );
+        }
+    }
+
+    /// Returns the operator index for each lane of the channel axis of the wave function with given `shape`,
+    /// that is broadcast to the shape of the lanes.
+    fn lane_operators<'a>(&'a self, shape: &[usize]) -> ArrayViewD<'a, usize> {
+        let lanes_shape: Vec<usize> = shape.iter()
+            .enumerate()
+            .filter_map(|(axis, &size)| (axis != self.dimension_no).then_some(size))
+            .collect();
+
+        match &self.indexing {
+            OperatorIndexing::Lanes(lane_indices) => {
+                assert!(
+                    lane_indices.len() == lanes_shape.iter().product::<usize>(),
+                    "Number of operators has to match the number of lanes of the channel axis"
+                );
 
-        self.operators.iter()
-            .zip(wave_function.array.lanes_mut(Axis(self.dimension_no)))
-            .par_bridge()
-            .into_par_iter()
-            .for_each(|(op, mut lane)| lane.assign(&op.dot(&lane)));
+                ArrayView::from_shape(IxDyn(&lanes_shape), lane_indices).unwrap()
+            }
+            OperatorIndexing::Indexed { positions, lane_indices } => {
+                assert!(lane_indices.ndim() <= lanes_shape.len(), "Index axes have to be axes of the wave function");
+                for &position in positions {
+                    assert!(
+                        lanes_shape[position] == lane_indices.shape()[position],
+                        "Indices shape has to match the shape of the index axes"
+                    );
+                }
+
+                let mut lane_indices = lane_indices.view();
+                while lane_indices.ndim() < lanes_shape.len() {
+                    lane_indices.insert_axis_inplace(Axis(lane_indices.ndim()));
+                }
+
+                lane_indices
+            }
+        }
+    }
+
+    fn apply_unchecked(&self, wave_function: &mut WaveFunction) {
+        let shape = wave_function.array().shape().to_vec();
+        let lane_operators = self.lane_operators(&shape);
+        apply_channel_operators(wave_function.array_mut(), self.dimension_no, &self.operators, lane_operators);
     }
 
     pub fn set_loss_checked(&mut self, loss_checked: LossChecker) {
@@ -41,8 +174,70 @@ impl NonDiagPropagator {
     }
 }
 
+/// Multiplies each lane of the `array` along `channel_axis` by the operator `operators[lane_operators[lane]]`,
+/// where `lane_operators` is broadcast to the shape of the remaining axes.
+pub(crate) fn apply_channel_operators(
+    array: &mut ArrayD<Complex64>,
+    channel_axis: usize,
+    operators: &[Array2<Complex64>],
+    lane_operators: ArrayViewD<usize>,
+) {
+    let size = array.shape()[channel_axis];
+    assert!(
+        operators.iter().all(|op| op.shape() == [size, size]),
+        "Operators size has to match the length of the channel axis"
+    );
+
+    let lane_operators = lane_operators
+        .broadcast(array.raw_dim().remove_axis(Axis(channel_axis)))
+        .expect("Indices shape has to match the shape of the index axes");
+
+    Zip::from(array.lanes_mut(Axis(channel_axis)))
+        .and(lane_operators)
+        .into_par_iter()
+        .for_each_init(
+            || Array1::<Complex64>::zeros(size),
+            |buffer, (mut lane, &op)| {
+                general_mat_vec_mul(Complex64::from(1.0), &operators[op], &lane, Complex64::from(0.0), buffer);
+                lane.assign(buffer);
+            },
+        );
+}
+
+/// Returns distinct `operators` and the index of the distinct operator for each of the given operators.
+pub(crate) fn deduplicate(operators: Vec<Array2<Complex64>>) -> (Vec<Array2<Complex64>>, Vec<usize>) {
+    let mut unique: Vec<Array2<Complex64>> = Vec::new();
+    let mut by_hash: HashMap<u64, Vec<usize>> = HashMap::new();
+    let mut indices = Vec::with_capacity(operators.len());
+
+    for operator in operators {
+        let mut hasher = DefaultHasher::new();
+        operator.shape().hash(&mut hasher);
+        for value in operator.iter() {
+            value.re.to_bits().hash(&mut hasher);
+            value.im.to_bits().hash(&mut hasher);
+        }
+
+        let candidates = by_hash.entry(hasher.finish()).or_default();
+        match candidates.iter().find(|&&i| unique[i] == operator) {
+            Some(&i) => indices.push(i),
+            None => {
+                candidates.push(unique.len());
+                indices.push(unique.len());
+                unique.push(operator);
+            }
+        }
+    }
+
+    (unique, indices)
+}
+
 impl Propagator for NonDiagPropagator {
-    fn apply(&mut self, wave_function: &mut WaveFunction) {
+    fn name(&self) -> &str {
+        "NonDiagPropagator"
+    }
+
+    fn apply(&mut self, wave_function: &mut WaveFunction, time: f64) {
         if let Some(loss_checker) = &mut self.loss_checked {
             loss_checker.check_before(wave_function);
         }
@@ -50,7 +245,7 @@ impl Propagator for NonDiagPropagator {
         self.apply_unchecked(wave_function);
 
         if let Some(loss_checker) = &mut self.loss_checked {
-            loss_checker.check_after(wave_function);
+            loss_checker.check_after(wave_function, time);
         }
     }
 
@@ -58,9 +253,33 @@ impl Propagator for NonDiagPropagator {
         &self.loss_checked
     }
 
+    fn loss_mut(&mut self) -> &mut Option<LossChecker> {
+        &mut self.loss_checked
+    }
+
     fn loss_reset(&mut self) {
         if let Some(loss_checker) = &mut self.loss_checked {
             loss_checker.reset();
         }
     }
+
+    fn follows_time_grid(&self) -> bool {
+        self.generator.is_some()
+    }
+
+    fn update_time_grid(&mut self, _previous: &TimeGrid, time_grid: &TimeGrid) {
+        if let Some(generator) = &self.generator {
+            self.operators = generator.operators(time_grid);
+        }
+    }
+
+    fn description(&self) -> OperationDescription {
+        match &self.generator {
+            Some(generator) => generator.description(vec![self.dimension_no]),
+            None => OperationDescription {
+                dimensions: vec![self.dimension_no],
+                ..Default::default()
+            },
+        }
+    }
 }
@@ -1,15 +1,20 @@
-use ndarray::{Array1, Axis};
+use ndarray::{Array1, Axis, Ix1};
 use num::complex::Complex64;
 use rayon::prelude::*;
 
-use crate::{loss_checker::LossChecker, wave_function::WaveFunction};
+use crate::{loss_checker::LossChecker, manifest::OperationDescription, time_grid::TimeGrid, wave_function::WaveFunction};
 
-use super::Propagator;
+use super::{operator_generator::OperatorGenerator, Propagator};
 
+/// Propagator acting with diagonal operator along single dimension.
+/// If created from a hamiltonian, the operator is regenerated when the [`TimeGrid`] changes,
+/// keeping the factors added by `add_operator`.
 #[derive(Clone)]
 pub struct OneDimPropagator {
     dimension_no: usize,
     operator: Array1<Complex64>,
+    generator: Option<OperatorGenerator<Ix1>>,
+    factors: Option<Array1<Complex64>>,
     loss_checked: Option<LossChecker>,
 }
 
@@ -18,6 +23,8 @@ impl OneDimPropagator {
         OneDimPropagator {
             dimension_no,
             operator: Array1::<Complex64>::ones(shape),
+            generator: None,
+            factors: None,
             loss_checked: None,
         }
     }
@@ -26,19 +33,29 @@ impl OneDimPropagator {
         assert!(operator.shape()[0] == self.operator.shape()[0]);
 
         self.operator = operator;
+        self.generator = None;
+        self.factors = None;
+    }
+
+    /// Sets the operator generated by `generator` for given [`TimeGrid`].
+    pub(crate) fn set_generator(&mut self, generator: OperatorGenerator<Ix1>, time_grid: &TimeGrid) {
+        self.set_operator(generator.operator(time_grid));
+        self.generator = Some(generator);
     }
 
     pub fn add_operator(&mut self, operator: Array1<Complex64>) {
         assert!(operator.shape()[0] == self.operator.shape()[0]);
 
         self.operator *= &operator;
+        match &mut self.factors {
+            Some(factors) => *factors *= &operator,
+            None => self.factors = Some(operator),
+        }
     }
 
     fn apply_unchecked(&self, wave_function: &mut WaveFunction) {
-        wave_function.change_observer.possible_norm_change = true;
-
         wave_function
-            .array
+            .array_mut()
             .lanes_mut(Axis(self.dimension_no))
             .into_iter()
             .par_bridge()
@@ -51,8 +68,12 @@ impl OneDimPropagator {
 }
 
 impl Propagator for OneDimPropagator {
+    fn name(&self) -> &str {
+        "OneDimPropagator"
+    }
+
     #[inline(always)]
-    fn apply(&mut self, wave_function: &mut WaveFunction) {
+    fn apply(&mut self, wave_function: &mut WaveFunction, time: f64) {
         if let Some(loss_checker) = &mut self.loss_checked {
             loss_checker.check_before(wave_function);
         }
@@ -60,7 +81,7 @@ impl Propagator for OneDimPropagator {
         self.apply_unchecked(wave_function);
 
         if let Some(loss_checker) = &mut self.loss_checked {
-            loss_checker.check_after(wave_function);
+            loss_checker.check_after(wave_function, time);
         }
     }
 
@@ -68,9 +89,36 @@ impl Propagator for OneDimPropagator {
         &self.loss_checked
     }
 
+    fn loss_mut(&mut self) -> &mut Option<LossChecker> {
+        &mut self.loss_checked
+    }
+
     fn loss_reset(&mut self) {
         if let Some(loss_checker) = &mut self.loss_checked {
             loss_checker.reset();
         }
     }
+
+    fn follows_time_grid(&self) -> bool {
+        self.generator.is_some()
+    }
+
+    fn update_time_grid(&mut self, _previous: &TimeGrid, time_grid: &TimeGrid) {
+        if let Some(generator) = &self.generator {
+            self.operator = generator.operator(time_grid);
+            if let Some(factors) = &self.factors {
+                self.operator *= factors;
+            }
+        }
+    }
+
+    fn description(&self) -> OperationDescription {
+        match &self.generator {
+            Some(generator) => generator.description(vec![self.dimension_no]),
+            None => OperationDescription {
+                dimensions: vec![self.dimension_no],
+                ..Default::default()
+            },
+        }
+    }
 }
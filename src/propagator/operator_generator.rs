@@ -0,0 +1,97 @@
+use ndarray::{Array, Array2, Dimension};
+use num::complex::Complex64;
+use rayon::prelude::*;
+
+use crate::{
+    linear_algebra::{hermitian_exponential, matrix_exponential},
+    manifest::{array_hash, OperationDescription},
+    time_grid::{select_step, TimeGrid, TimeStep},
+};
+
+/// Rule of exponentiating the generating hamiltonian.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Exponentiation {
+    /// exp(-i H dt)
+    Hamiltonian,
+    /// exp(-W |dt|) of absorbing potential W, damping both in real and imaginary time
+    Absorbing,
+}
+
+/// Diagonal hamiltonian generating the operator of a propagator with given [`TimeStep`],
+/// used to regenerate the operator when the [`TimeGrid`] changes.
+#[derive(Clone)]
+pub(crate) struct OperatorGenerator<D: Dimension> {
+    hamiltonian: Array<Complex64, D>,
+    exponentiation: Exponentiation,
+    step: TimeStep,
+}
+
+impl<D: Dimension> OperatorGenerator<D> {
+    pub(crate) fn new(hamiltonian: Array<Complex64, D>, exponentiation: Exponentiation, step: TimeStep) -> Self {
+        Self { hamiltonian, exponentiation, step }
+    }
+
+    /// Returns the operator for given [`TimeGrid`].
+    pub(crate) fn operator(&self, time_grid: &TimeGrid) -> Array<Complex64, D> {
+        let dt = select_step(self.step, time_grid);
+
+        match self.exponentiation {
+            Exponentiation::Hamiltonian => self.hamiltonian.map(|x| Complex64::exp(-Complex64::i() * x * dt)),
+            Exponentiation::Absorbing => self.hamiltonian.map(|x| (-x * dt.norm()).exp()),
+        }
+    }
+
+    /// Returns the description of the generated propagator acting along `dimensions`.
+    pub(crate) fn description(&self, dimensions: Vec<usize>) -> OperationDescription {
+        OperationDescription {
+            step: Some(format!("{:?}", self.step)),
+            dimensions,
+            generator: Some(format!("{:?}", self.exponentiation)),
+            hamiltonian_hash: Some(array_hash([&self.hamiltonian])),
+            ..Default::default()
+        }
+    }
+}
+
+/// Channel matrices generating the operators of a non diagonal propagator with given [`TimeStep`].
+#[derive(Clone)]
+pub(crate) struct MatrixGenerator {
+    matrices: Vec<Array2<Complex64>>,
+    hermitian: bool,
+    step: TimeStep,
+}
+
+impl MatrixGenerator {
+    pub(crate) fn new(matrices: Vec<Array2<Complex64>>, hermitian: bool, step: TimeStep) -> Self {
+        Self { matrices, hermitian, step }
+    }
+
+    /// Returns exponentials of the matrices for given [`TimeGrid`], calculated in parallel.
+    pub(crate) fn operators(&self, time_grid: &TimeGrid) -> Vec<Array2<Complex64>> {
+        let factor = -Complex64::i() * select_step(self.step, time_grid);
+
+        self.matrices
+            .par_iter()
+            .map(|matrix| {
+                if self.hermitian {
+                    hermitian_exponential(matrix, factor)
+                } else {
+                    matrix_exponential(&(matrix * factor))
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the description of the generated propagator acting along `dimensions`.
+    pub(crate) fn description(&self, dimensions: Vec<usize>) -> OperationDescription {
+        let generator = if self.hermitian { "HermitianMatrix" } else { "Matrix" };
+
+        OperationDescription {
+            step: Some(format!("{:?}", self.step)),
+            dimensions,
+            generator: Some(generator.to_string()),
+            hamiltonian_hash: Some(array_hash(&self.matrices)),
+            ..Default::default()
+        }
+    }
+}
@@ -1,22 +1,45 @@
-use super::{n_dim_propagator::NDimPropagator, one_dim_propagator::OneDimPropagator};
+use super::{
+    n_dim_propagator::NDimPropagator,
+    non_diagonal_propagator::{deduplicate, NonDiagPropagator},
+    one_dim_propagator::OneDimPropagator,
+    operator_generator::{Exponentiation, MatrixGenerator, OperatorGenerator},
+};
 use crate::{
     grid::Grid,
-    time_grid::{select_step, TimeGrid, TimeStep},
+    time_grid::{TimeGrid, TimeStep},
 };
-use ndarray::{Array1, ArrayD};
+use ndarray::{Array1, Array2, ArrayD, IxDyn};
 use num::complex::Complex64;
+use rayon::prelude::*;
 
 /// Creates propagator from one dimensional hamiltonian acting on given [`Grid`] with given [`TimeGrid`] and [`Step`].
+/// The propagator is regenerated from the hamiltonian when the [`TimeGrid`] changes.
 pub fn one_dim_into_propagator(
     hamiltonian: Array1<f64>,
     grid: &Grid,
     time: &TimeGrid,
     step: TimeStep,
 ) -> OneDimPropagator {
-    let dt = select_step(step, time);
+    let generator = OperatorGenerator::new(hamiltonian.mapv(Complex64::from), Exponentiation::Hamiltonian, step);
 
     let mut propagator = OneDimPropagator::new(grid.nodes_no, grid.dimension_no);
-    propagator.set_operator(hamiltonian.map(|x| Complex64::exp(-Complex64::i() * x * dt)));
+    propagator.set_generator(generator, time);
+
+    propagator
+}
+
+/// Creates propagator from one dimensional absorbing potential W acting on given [`Grid`] with given [`TimeGrid`] and [`Step`].
+/// Absorbing hamiltonian -iW damps the wave function by exp(-W |dt|) both in real and imaginary time.
+pub fn absorbing_into_propagator(
+    absorbing_potential: Array1<f64>,
+    grid: &Grid,
+    time: &TimeGrid,
+    step: TimeStep,
+) -> OneDimPropagator {
+    let generator = OperatorGenerator::new(absorbing_potential.mapv(Complex64::from), Exponentiation::Absorbing, step);
+
+    let mut propagator = OneDimPropagator::new(grid.nodes_no, grid.dimension_no);
+    propagator.set_generator(generator, time);
 
     propagator
 }
@@ -27,12 +50,7 @@ pub fn n_dim_into_propagator(
     time: &TimeGrid,
     step: TimeStep,
 ) -> NDimPropagator {
-    let dt = select_step(step, time);
-
-    let mut propagator = NDimPropagator::new();
-    propagator.set_operator(hamiltonian.map(|x| Complex64::exp(-Complex64::i() * x * dt)));
-
-    propagator
+    complex_n_dim_into_propagator(hamiltonian.mapv(Complex64::from), time, step)
 }
 
 /// Creates propagator from n dimensional complex hamiltonian with given [`TimeGrid`] and [`Step`].
@@ -41,10 +59,95 @@ pub fn complex_n_dim_into_propagator(
     time: &TimeGrid,
     step: TimeStep,
 ) -> NDimPropagator {
-    let dt = select_step(step, time);
+    let generator = OperatorGenerator::new(hamiltonian, Exponentiation::Hamiltonian, step);
 
     let mut propagator = NDimPropagator::new();
-    propagator.set_operator(hamiltonian.map(|x| Complex64::exp(-Complex64::i() * x * dt)));
+    propagator.set_generator(generator, time);
+
+    propagator
+}
+
+/// Creates propagator from hermitian channel matrix `hamiltonian` acting on `channel_dimension`
+/// and depending on the nodes of `grids`, evaluated at each grid point with given [`TimeGrid`] and [`Step`].
+/// The exponential is calculated using the eigendecomposition.
+pub fn hermitian_matrix_into_propagator<F>(
+    hamiltonian: F,
+    grids: &[&Grid],
+    channel_dimension: usize,
+    time: &TimeGrid,
+    step: TimeStep,
+) -> NonDiagPropagator
+where
+    F: Fn(&[f64]) -> Array2<Complex64> + Sync,
+{
+    let (matrices, index_axes, indices) = evaluate_on_grids(hamiltonian, grids);
+
+    channel_matrices_into_propagator(matrices, index_axes, indices, channel_dimension, true, time, step)
+}
+
+/// Creates propagator from general complex channel matrix `hamiltonian` acting on `channel_dimension`,
+/// e.g. with complex absorbing potential, depending on the nodes of `grids` with given [`TimeGrid`] and [`Step`].
+/// The exponential is calculated using the Padé approximant with scaling and squaring.
+pub fn complex_matrix_into_propagator<F>(
+    hamiltonian: F,
+    grids: &[&Grid],
+    channel_dimension: usize,
+    time: &TimeGrid,
+    step: TimeStep,
+) -> NonDiagPropagator
+where
+    F: Fn(&[f64]) -> Array2<Complex64> + Sync,
+{
+    let (matrices, index_axes, indices) = evaluate_on_grids(hamiltonian, grids);
+
+    channel_matrices_into_propagator(matrices, index_axes, indices, channel_dimension, false, time, step)
+}
+
+/// Creates propagator from indexed channel `matrices`, see [`NonDiagPropagator::set_indexed_operators`].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn channel_matrices_into_propagator(
+    matrices: Vec<Array2<Complex64>>,
+    index_axes: Vec<usize>,
+    indices: ArrayD<usize>,
+    channel_dimension: usize,
+    hermitian: bool,
+    time: &TimeGrid,
+    step: TimeStep,
+) -> NonDiagPropagator {
+    let generator = MatrixGenerator::new(matrices, hermitian, step);
+
+    let mut propagator = NonDiagPropagator::new(channel_dimension);
+    propagator.set_generator(generator, index_axes, indices, time);
 
     propagator
 }
+
+/// Returns distinct matrices of `function` evaluated in parallel at each point of `grids`,
+/// together with index axes and indices as used by [`NonDiagPropagator::set_indexed_operators`].
+/// Matrices repeating along the grids, e.g. for coupling constant along one of the axes, are stored once.
+pub(crate) fn evaluate_on_grids<F>(function: F, grids: &[&Grid]) -> (Vec<Array2<Complex64>>, Vec<usize>, ArrayD<usize>)
+where
+    F: Fn(&[f64]) -> Array2<Complex64> + Sync,
+{
+    let shape: Vec<usize> = grids.iter().map(|grid| grid.nodes_no).collect();
+    let points_no = shape.iter().product();
+
+    let matrices: Vec<Array2<Complex64>> = (0..points_no)
+        .into_par_iter()
+        .map(|mut point| {
+            let mut nodes = vec![0.0; grids.len()];
+            for (node, grid) in nodes.iter_mut().zip(grids).rev() {
+                *node = grid.nodes[point % grid.nodes_no];
+                point /= grid.nodes_no;
+            }
+
+            function(&nodes)
+        })
+        .collect();
+
+    let (matrices, indices) = deduplicate(matrices);
+    let indices = ArrayD::from_shape_vec(IxDyn(&shape), indices).unwrap();
+    let index_axes = grids.iter().map(|grid| grid.dimension_no).collect();
+
+    (matrices, index_axes, indices)
+}
@@ -1,17 +1,24 @@
-use crate::{grid::Grid, wave_function::WaveFunction};
+use crate::{grid::Grid, manifest::OperationDescription, wave_function::WaveFunction};
 
-use super::transformation::Transformation;
-use ndarray::{Array2, Axis, Zip};
+use super::{
+    matrix_transformation::{matrix_along_view_axis, prepare_buffer},
+    transformation::Transformation,
+};
+use ndarray::{Array2, ArrayD, Axis};
 use num::complex::Complex64;
+use rayon::prelude::*;
 
 /// Diagonalization to operator eigenspace using matrix transformation.
+/// The transformation is performed as matrix-matrix products into a buffer reused between steps.
 #[derive(Clone)]
 pub struct StateMatrixTransformation {
     dimension_no: usize,
+    dimension_size: usize,
     dimension_no_dependent: usize,
 
     transformations: Vec<Array2<Complex64>>,
     inverse_transformations: Vec<Array2<Complex64>>,
+    buffer: ArrayD<Complex64>,
 
     pub grid_transformation: Grid,
 }
@@ -22,53 +29,89 @@ impl StateMatrixTransformation {
         grid: &Grid,
         grid_transformation: Grid,
     ) -> Self {
-        assert!(dimension_no_dependent > grid.dimension_no, "Dependent dimension should have higher dimension index");
+        assert!(dimension_no_dependent != grid.dimension_no, "Dependent dimension has to differ from transformed dimension");
 
         StateMatrixTransformation {
             dimension_no: grid.dimension_no,
+            dimension_size: grid.nodes_no,
             dimension_no_dependent,
             transformations: Vec::new(),
             inverse_transformations: Vec::new(),
+            buffer: ArrayD::zeros(vec![0]),
             grid_transformation,
         }
     }
 
+    /// Sets transformation matrices for each index of the dependent dimension,
+    /// their number has to match the length of the dependent dimension of the transformed wave function.
     pub fn set_diagonalization_matrices(
         &mut self,
         transformations: Vec<Array2<Complex64>>,
         inverse_transformations: Vec<Array2<Complex64>>,
     ) {
+        assert!(
+            transformations.len() == inverse_transformations.len(),
+            "Number of transformations and inverse transformations have to be equal"
+        );
+        assert!(
+            transformations.iter()
+                .chain(inverse_transformations.iter())
+                .all(|t| t.shape() == [self.dimension_size, self.dimension_size]),
+            "Transformation matrices have to be square with the size of the transformed grid"
+        );
+
         self.transformations = transformations;
         self.inverse_transformations = inverse_transformations;
     }
 }
 
+impl StateMatrixTransformation {
+    fn apply_matrices(&mut self, wave_function: &mut WaveFunction, inverse: bool) {
+        let matrices = if inverse { &self.inverse_transformations } else { &self.transformations };
+        assert!(
+            matrices.len() == wave_function.array().shape()[self.dimension_no_dependent],
+            "Number of transformation matrices has to match the length of the dependent dimension"
+        );
+
+        wave_function.swap_grid(self.dimension_no, &mut self.grid_transformation);
+
+        // Transformed axis index within the block of fixed dependent index
+        let axis = if self.dimension_no_dependent < self.dimension_no {
+            self.dimension_no - 1
+        } else {
+            self.dimension_no
+        };
+
+        prepare_buffer(wave_function.array_mut(), &mut self.buffer);
+        wave_function.array().axis_iter(Axis(self.dimension_no_dependent))
+            .into_par_iter()
+            .zip(self.buffer.axis_iter_mut(Axis(self.dimension_no_dependent)))
+            .zip(matrices.par_iter())
+            .for_each(|((input, output), t)| matrix_along_view_axis(t, input, output, axis));
+        std::mem::swap(wave_function.array_mut(), &mut self.buffer);
+    }
+}
+
 impl Transformation for StateMatrixTransformation {
+    fn name(&self) -> &str {
+        "StateMatrixTransformation"
+    }
+
     #[inline(always)]
     fn transform(&mut self, wave_function: &mut WaveFunction) {
-        wave_function.grids[self.dimension_no].swap(&mut self.grid_transformation);
-        wave_function.change_observer.possible_norm_change = true;
-
-        self.transformations.iter()
-            .zip(wave_function.array.axis_iter_mut(Axis(self.dimension_no_dependent)))
-            .for_each(|(t, mut array)| {
-                Zip::from(array.lanes_mut(Axis(self.dimension_no)))
-                    .par_for_each(|mut lane| lane.assign(&t.dot(&lane)))
-            }
-        )
+        self.apply_matrices(wave_function, false);
     }
 
     #[inline(always)]
     fn inverse_transform(&mut self, wave_function: &mut WaveFunction) {
-        wave_function.grids[self.dimension_no].swap(&mut self.grid_transformation);
-        wave_function.change_observer.possible_norm_change = true;
-
-        self.inverse_transformations.iter()
-            .zip(wave_function.array.axis_iter_mut(Axis(self.dimension_no_dependent)))
-            .for_each(|(t, mut array)| {
-                Zip::from(array.lanes_mut(Axis(self.dimension_no)))
-                    .par_for_each(|mut lane| lane.assign(&t.dot(&lane)))
-            }
-        )
+        self.apply_matrices(wave_function, true);
+    }
+
+    fn description(&self) -> OperationDescription {
+        OperationDescription {
+            dimensions: vec![self.dimension_no, self.dimension_no_dependent],
+            grids: vec![self.grid_transformation.name.clone()],
+            ..Default::default()
+        }
     }
 }
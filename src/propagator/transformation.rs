@@ -1,13 +1,21 @@
 
-use crate::wave_function::WaveFunction;
+use crate::{manifest::OperationDescription, wave_function::WaveFunction};
 
 /// Trait for diagonalization of operator, transforming [`WaveFunction`] in give space and grids to operator eigenspace.
 pub trait Transformation {
+    /// Returns the name of the transformation.
+    fn name(&self) -> &str;
+
     /// Diagonalizes given [`WaveFunction`] to operator eigenspace.
     fn transform(&mut self, wave_function: &mut WaveFunction);
 
     /// Return [`WaveFunction`] to original space.
     fn inverse_transform(&mut self, wave_function: &mut WaveFunction);
+
+    /// Returns the description of the transformation written into the run manifest.
+    fn description(&self) -> OperationDescription {
+        OperationDescription::default()
+    }
 }
 
 /// Define whether diagonalize or inverse_diagonalize is performed first
@@ -0,0 +1,165 @@
+use std::{
+    fs::File,
+    io::{stdout, Stdout, Write},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::wave_function::WaveFunction;
+
+/// Type of the operation performed during propagation step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperationKind {
+    Propagator,
+    Transformation,
+    Saver,
+    Control,
+}
+
+/// Cumulative loss observed by the [`LossChecker`](crate::loss_checker::LossChecker) of an operation.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LossReport {
+    pub name: String,
+    pub loss: f64,
+}
+
+/// Summary of a single operation in the operation stack.
+/// `region_losses` are losses attributed to separate regions of the operation, e.g. absorbing boundaries.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OperationReport {
+    pub name: String,
+    pub kind: OperationKind,
+    pub loss: Option<LossReport>,
+    pub region_losses: Vec<LossReport>,
+}
+
+/// Elapsed wall time in seconds spent in each type of operation.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ElapsedTimes {
+    pub propagators: f64,
+    pub transformations: f64,
+    pub savers: f64,
+    pub controls: f64,
+}
+
+impl ElapsedTimes {
+    /// Adds elapsed `duration` to the given operation `kind`.
+    pub fn add(&mut self, kind: OperationKind, duration: Duration) {
+        let elapsed = match kind {
+            OperationKind::Propagator => &mut self.propagators,
+            OperationKind::Transformation => &mut self.transformations,
+            OperationKind::Saver => &mut self.savers,
+            OperationKind::Control => &mut self.controls,
+        };
+
+        *elapsed += duration.as_secs_f64();
+    }
+}
+
+/// Early termination of the propagation by the [`StopCondition`](crate::stop_condition::StopCondition)
+/// named `condition` after `step_no` steps at given `time`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StopReport {
+    pub condition: String,
+    pub step_no: usize,
+    pub time: f64,
+}
+
+/// Report of the propagation returned by [`Propagation::propagate`](crate::propagation::Propagation::propagate).
+/// - `step_no` is the number of performed steps.
+/// - `operations` are the operations in the stack order with their cumulative losses.
+/// - `final_norm` is the norm of the wave function at the end of the propagation.
+/// - `elapsed` is the wall time spent in each type of operation.
+/// - `stop` is the stop condition that terminated the propagation early, if any.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PropagationReport {
+    pub step_no: usize,
+    pub operations: Vec<OperationReport>,
+    pub final_norm: f64,
+    pub elapsed: ElapsedTimes,
+    pub stop: Option<StopReport>,
+}
+
+impl PropagationReport {
+    /// Returns the report serialized to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// Saves the report as JSON file with given name in current directory.
+    pub fn save(&self, name: &str) -> Result<(), &str> {
+        let path = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let file = File::create(format!("{path}/{name}.json"));
+        let Ok(mut file) = file else {
+            return Err("Failed to create report file");
+        };
+
+        if file.write_all(self.to_json().as_bytes()).is_err() {
+            return Err("Failed to save report");
+        }
+
+        Ok(())
+    }
+}
+
+/// Trait for reporting the progress of the propagation.
+pub trait Reporter {
+    /// Reports the start of the step `step` at given `time`.
+    fn step(&mut self, step: usize, time: f64, wave_function: &mut WaveFunction);
+
+    /// Reports the end of the propagation.
+    fn finish(&mut self, report: &PropagationReport);
+}
+
+/// Writes the progress of the propagation every `frequency` steps and the losses at its end,
+/// by default to the standard output.
+#[derive(Clone)]
+pub struct PrintReporter<W: Write = Stdout> {
+    frequency: usize,
+    writer: W,
+}
+
+impl PrintReporter {
+    /// Creates new `PrintReporter` printing every `frequency` steps to the standard output.
+    pub fn new(frequency: usize) -> Self {
+        PrintReporter::with_writer(frequency, stdout())
+    }
+}
+
+impl<W: Write> PrintReporter<W> {
+    /// Creates new `PrintReporter` writing every `frequency` steps to given `writer`.
+    pub fn with_writer(frequency: usize, writer: W) -> Self {
+        assert!(frequency > 0, "Frequency has to be positive");
+
+        PrintReporter { frequency, writer }
+    }
+}
+
+impl<W: Write> Reporter for PrintReporter<W> {
+    fn step(&mut self, step: usize, time: f64, wave_function: &mut WaveFunction) {
+        if step.is_multiple_of(self.frequency) {
+            writeln!(self.writer, "step no: {}, time: {}, norm: {}", step, time, wave_function.norm()).unwrap();
+        }
+    }
+
+    fn finish(&mut self, report: &PropagationReport) {
+        for operation in &report.operations {
+            if let Some(loss) = &operation.loss {
+                writeln!(self.writer, "{} loss: {}", loss.name, loss.loss).unwrap();
+            }
+            for loss in &operation.region_losses {
+                writeln!(self.writer, "{} region {} loss: {}", operation.name, loss.name, loss.loss).unwrap();
+            }
+        }
+        if let Some(stop) = &report.stop {
+            writeln!(self.writer, "stopped by {} at step no: {}, time: {}", stop.condition, stop.step_no, stop.time).unwrap();
+        }
+        writeln!(self.writer, "final norm: {}", report.final_norm).unwrap();
+    }
+}
@@ -1,14 +1,93 @@
 
+use ndarray::ArrayD;
+
 use crate::wave_function::WaveFunction;
 
 /// Trait for monitoring the state of the wave function throughout the simulation and saving it.
 pub trait Saver {
-    /// Monitor the state of the wave function.
-    fn monitor(&mut self, wave_function: &mut WaveFunction);
+    /// Returns the name of the saver.
+    fn name(&self) -> &str;
+
+    /// Monitor the state of the wave function at given `time`.
+    fn monitor(&mut self, wave_function: &mut WaveFunction, time: f64);
 
     /// Save collected data.
     fn save(&self) -> Result<(), &str>;
 
+    /// Returns collected data as named arrays, with the same names as the files written by `save`.
+    fn outputs(&self) -> Vec<(String, ArrayD<f64>)> {
+        Vec::new()
+    }
+
+    /// Returns names of the data returned by `outputs`,
+    /// savers collecting large data should override it to avoid copying the data.
+    fn output_names(&self) -> Vec<String> {
+        self.outputs().into_iter().map(|(name, _)| name).collect()
+    }
+
     /// Reset collected data
     fn reset(&mut self);
 }
+
+/// Chooses the frames monitored by savers evenly spaced in time.
+/// The first monitored time is the first frame and a next frame is due after every `duration / frames_no`,
+/// so the sampling depends only on the passed times and not on the number of calls per step.
+/// Repeated calls within the same interval are sampled once.
+#[derive(Clone, Debug)]
+pub struct FrameSampler {
+    interval: f64,
+    frames_no: usize,
+    start: Option<f64>,
+    next_frame: usize,
+    sampled: usize,
+}
+
+impl FrameSampler {
+    /// Creates new `FrameSampler` monitoring `frames_no` frames during propagation lasting `duration`.
+    pub fn new(duration: f64, frames_no: usize) -> Self {
+        assert!(frames_no > 0, "Number of frames has to be positive");
+
+        FrameSampler {
+            interval: duration.abs() / frames_no as f64,
+            frames_no,
+            start: None,
+            next_frame: 0,
+            sampled: 0,
+        }
+    }
+
+    /// Returns the number of frames to be monitored.
+    pub fn frames_no(&self) -> usize {
+        self.frames_no
+    }
+
+    /// Returns index of the frame to be monitored at given `time` or `None` if no frame is due.
+    pub fn sample(&mut self, time: f64) -> Option<usize> {
+        if self.sampled >= self.frames_no {
+            return None;
+        }
+
+        let start = *self.start.get_or_insert(time);
+        let position = if self.interval > 0.0 {
+            // tolerance for the accumulated rounding of the time
+            (time - start).abs() / self.interval + 1e-9
+        } else {
+            self.next_frame as f64
+        };
+        if position < self.next_frame as f64 {
+            return None;
+        }
+
+        self.next_frame = position.floor() as usize + 1;
+        self.sampled += 1;
+
+        Some(self.sampled - 1)
+    }
+
+    /// Resets the sampling to start again from the next monitored time.
+    pub fn reset(&mut self) {
+        self.start = None;
+        self.next_frame = 0;
+        self.sampled = 0;
+    }
+}
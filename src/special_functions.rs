@@ -1,3 +1,7 @@
+use std::f64::consts::PI;
+
+use crate::linear_algebra::tridiagonal_eigenvalues;
+
 
 /// Returns the legendre polynomials up to order `j` at `x`.
 pub fn legendre_polynomials(j: usize, x: f64) -> Vec<f64> {
@@ -115,6 +119,220 @@ pub fn normalization(l: u32, m: i32) -> f64 {
     (norm2 * (l as f64 + 0.5)).sqrt()
 }
 
+/// Returns nodes in ascending order and weights of Gauss-Legendre quadrature of order `n` on [-1, 1].
+pub fn gauss_legendre_quadrature(n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut nodes = vec![0.0; n];
+    let mut weights = vec![0.0; n];
+
+    for i in 0..n.div_ceil(2) {
+        let mut z = (PI * (i as f64 + 0.75) / (n as f64 + 0.5)).cos();
+        let mut derivative;
+        loop {
+            let (p_n, p_n_1) = legendre_pair(n, z);
+            derivative = n as f64 * (z * p_n - p_n_1) / (z * z - 1.0);
+
+            let previous = z;
+            z = previous - p_n / derivative;
+            if (z - previous).abs() <= 1e-15 {
+                break;
+            }
+        }
+
+        let weight = 2.0 / ((1.0 - z * z) * derivative * derivative);
+        nodes[i] = -z;
+        nodes[n - 1 - i] = z;
+        weights[i] = weight;
+        weights[n - 1 - i] = weight;
+    }
+
+    (nodes, weights)
+}
+
+/// Returns nodes in ascending order and weights of Gauss-Hermite quadrature of order `n`
+/// multiplied by e^(x^2), so that they integrate functions over x without the Gaussian weight.
+pub fn gauss_hermite_quadrature(n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut nodes = vec![0.0; n];
+    let mut weights = vec![0.0; n];
+    // Initial guesses from the eigenvalues of the Jacobi matrix, they are polished by Newton iterations
+    let off_diagonal: Vec<f64> = (1..n).map(|k| (k as f64 / 2.0).sqrt()).collect();
+    let guesses = tridiagonal_eigenvalues(&vec![0.0; n], &off_diagonal);
+
+    for i in 0..n.div_ceil(2) {
+        let mut z = guesses[n - 1 - i];
+        let mut scaled;
+        loop {
+            scaled = hermite_pair(n, z);
+            let (p_n, p_n_1, _) = scaled;
+
+            let previous = z;
+            z = previous - p_n / ((2.0 * n as f64).sqrt() * p_n_1);
+            if (z - previous).abs() <= 1e-14 * z.abs().max(1.0) {
+                break;
+            }
+        }
+        let (_, p_n_1, log_scale) = scaled;
+
+        // w e^(z^2) = 1 / (n phi_{n-1}(z)^2) with Hermite function phi_{n-1}(z) = p_{n-1}(z) e^(-z^2 / 2)
+        let weight = (z * z - (n as f64).ln() - 2.0 * (p_n_1.abs().ln() + log_scale)).exp();
+        nodes[n - 1 - i] = z;
+        nodes[i] = -z;
+        weights[n - 1 - i] = weight;
+        weights[i] = weight;
+    }
+
+    (nodes, weights)
+}
+
+/// Returns nodes in ascending order and weights of Gauss-Laguerre quadrature of order `n`
+/// multiplied by e^x, so that they integrate functions over x without the exponential weight.
+pub fn gauss_laguerre_quadrature(n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut nodes = vec![0.0; n];
+    let mut weights = vec![0.0; n];
+    // Initial guesses from the eigenvalues of the Jacobi matrix, they are polished by Newton iterations
+    let diagonal: Vec<f64> = (0..n).map(|k| 2.0 * k as f64 + 1.0).collect();
+    let off_diagonal: Vec<f64> = (1..n).map(|k| k as f64).collect();
+    let guesses = tridiagonal_eigenvalues(&diagonal, &off_diagonal);
+
+    for i in 0..n {
+        let mut z = guesses[i];
+        let mut scaled;
+        loop {
+            scaled = laguerre_pair(n, z);
+            let (p_n, p_n_1, _) = scaled;
+
+            let previous = z;
+            z = previous - p_n * z / (n as f64 * (p_n - p_n_1));
+            if (z - previous).abs() <= 1e-14 * z.abs().max(1.0) {
+                break;
+            }
+        }
+        let (_, p_n_1, log_scale) = scaled;
+
+        // w e^z = z / (n^2 L_{n-1}(z)^2)
+        let weight = (z.ln() + z - 2.0 * (n as f64).ln() - 2.0 * (p_n_1.abs().ln() + log_scale)).exp();
+        nodes[i] = z;
+        weights[i] = weight;
+    }
+
+    (nodes, weights)
+}
+
+/// Returns Legendre polynomials of order `n` and `n - 1` at `x`.
+fn legendre_pair(n: usize, x: f64) -> (f64, f64) {
+    let mut p_1 = 1.0;
+    let mut p_2 = 0.0;
+    for j in 1..=n {
+        let p_3 = p_2;
+        p_2 = p_1;
+        p_1 = ((2 * j - 1) as f64 * x * p_2 - (j - 1) as f64 * p_3) / j as f64;
+    }
+
+    (p_1, p_2)
+}
+
+/// Logarithm of the rescaling applied during the recurrences to avoid overflow.
+const RESCALE_LOG: f64 = 150.0 * std::f64::consts::LN_10;
+
+/// Returns orthonormal Hermite polynomials of order `n` and `n - 1` at `x`
+/// scaled by e^(-log_scale) together with `log_scale`.
+fn hermite_pair(n: usize, x: f64) -> (f64, f64, f64) {
+    let mut p_1 = PI.powf(-0.25);
+    let mut p_2 = 0.0;
+    let mut log_scale = 0.0;
+    for j in 1..=n {
+        let p_3 = p_2;
+        p_2 = p_1;
+        p_1 = x * (2.0 / j as f64).sqrt() * p_2 - ((j - 1) as f64 / j as f64).sqrt() * p_3;
+
+        if p_1.abs() > 1e150 {
+            p_1 *= 1e-150;
+            p_2 *= 1e-150;
+            log_scale += RESCALE_LOG;
+        }
+    }
+
+    (p_1, p_2, log_scale)
+}
+
+/// Returns Laguerre polynomials of order `n` and `n - 1` at `x`
+/// scaled by e^(-log_scale) together with `log_scale`.
+fn laguerre_pair(n: usize, x: f64) -> (f64, f64, f64) {
+    let mut p_1 = 1.0;
+    let mut p_2 = 0.0;
+    let mut log_scale = 0.0;
+    for j in 1..=n {
+        let p_3 = p_2;
+        p_2 = p_1;
+        p_1 = ((2 * j - 1) as f64 - x) * p_2 / j as f64 - (j - 1) as f64 * p_3 / j as f64;
+
+        if p_1.abs() > 1e150 {
+            p_1 *= 1e-150;
+            p_2 *= 1e-150;
+            log_scale += RESCALE_LOG;
+        }
+    }
+
+    (p_1, p_2, log_scale)
+}
+
+/// Returns Bessel functions J_n(`x`) for n = 0..=`n_max` calculated using Miller's backward recurrence.
+pub fn bessel_j_sequence(n_max: usize, x: f64) -> Vec<f64> {
+    let mut values = miller_recurrence(n_max, x.abs(), -1.0);
+    if x < 0.0 {
+        values.iter_mut().skip(1).step_by(2).for_each(|value| *value = -*value);
+    }
+
+    values
+}
+
+/// Returns exponentially scaled modified Bessel functions exp(-`x`) I_n(`x`) for n = 0..=`n_max` and `x` >= 0
+/// calculated using Miller's backward recurrence.
+pub fn scaled_bessel_i_sequence(n_max: usize, x: f64) -> Vec<f64> {
+    assert!(x >= 0.0, "Argument of scaled modified Bessel functions has to be non negative");
+
+    miller_recurrence(n_max, x, 1.0)
+}
+
+/// Backward recurrence f_(n-1) = 2n / x f_n + `sign` f_(n+1) normalized by f_0 + 2 sum f_n = 1,
+/// where for `sign` = -1 only even orders are summed, giving J_n(x), and for `sign` = 1 giving exp(-x) I_n(x).
+fn miller_recurrence(n_max: usize, x: f64, sign: f64) -> Vec<f64> {
+    let mut values = vec![0.0; n_max + 1];
+    if x == 0.0 {
+        values[0] = 1.0;
+        return values;
+    }
+
+    let start = (n_max.max(x as usize) + 10 * x.cbrt() as usize + 40).next_multiple_of(2);
+
+    let mut f_next = 0.0;
+    let mut f = 1e-300;
+    let mut sum = 0.0;
+    for n in (1..=start).rev() {
+        let f_previous = 2.0 * n as f64 / x * f + sign * f_next;
+        f_next = f;
+        f = f_previous;
+
+        if n - 1 <= n_max {
+            values[n - 1] = f;
+        }
+        if n > 1 && (sign > 0.0 || (n - 1).is_multiple_of(2)) {
+            sum += 2.0 * f;
+        }
+
+        if f.abs() > 1e250 {
+            f *= 1e-250;
+            f_next *= 1e-250;
+            sum *= 1e-250;
+            values.iter_mut().for_each(|value| *value *= 1e-250);
+        }
+    }
+    sum += f;
+
+    values.iter_mut().for_each(|value| *value /= sum);
+
+    values
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,173 @@
+use std::time::{Duration, Instant};
+
+use ndarray::ArrayD;
+
+use crate::{border_dumping::region_mask, grid::Grid, wave_function::WaveFunction};
+
+/// Trait for conditions that terminate the propagation before all steps of the time grid are performed.
+/// Conditions are checked at the step boundaries with the interval given in
+/// [`Propagation::add_stop_condition`](crate::propagation::Propagation::add_stop_condition).
+pub trait StopCondition {
+    /// Returns the name of the stop condition.
+    fn name(&self) -> &str;
+
+    /// Returns true if the propagation should stop at given `time`.
+    fn check(&mut self, time: f64, wave_function: &mut WaveFunction) -> bool;
+
+    /// Resets the state of the condition at the start of the propagation.
+    fn reset(&mut self) {}
+}
+
+/// Stops the propagation when the norm of the wave function drops below `threshold`,
+/// e.g. when the wave packet was absorbed.
+#[derive(Clone)]
+pub struct NormThreshold {
+    threshold: f64,
+}
+
+impl NormThreshold {
+    /// Creates new `NormThreshold` with given `threshold`.
+    pub fn new(threshold: f64) -> Self {
+        assert!(threshold >= 0.0, "Threshold cannot be negative");
+
+        NormThreshold { threshold }
+    }
+}
+
+impl StopCondition for NormThreshold {
+    fn name(&self) -> &str {
+        "NormThreshold"
+    }
+
+    fn check(&mut self, _time: f64, wave_function: &mut WaveFunction) -> bool {
+        wave_function.norm() < self.threshold
+    }
+}
+
+/// Stops the propagation when the `observable` of the wave function changes by less than `tolerance`
+/// between consecutive checks.
+#[derive(Clone)]
+pub struct ObservableConvergence<F>
+where
+    F: FnMut(&mut WaveFunction) -> f64,
+{
+    observable: F,
+    tolerance: f64,
+    previous: Option<f64>,
+}
+
+impl<F> ObservableConvergence<F>
+where
+    F: FnMut(&mut WaveFunction) -> f64,
+{
+    /// Creates new `ObservableConvergence` of the `observable` with absolute `tolerance`.
+    pub fn new(observable: F, tolerance: f64) -> Self {
+        assert!(tolerance > 0.0, "Tolerance has to be positive");
+
+        ObservableConvergence {
+            observable,
+            tolerance,
+            previous: None,
+        }
+    }
+
+    /// Returns the value of the observable at the last check.
+    pub fn value(&self) -> Option<f64> {
+        self.previous
+    }
+}
+
+impl<F> StopCondition for ObservableConvergence<F>
+where
+    F: FnMut(&mut WaveFunction) -> f64,
+{
+    fn name(&self) -> &str {
+        "ObservableConvergence"
+    }
+
+    fn check(&mut self, _time: f64, wave_function: &mut WaveFunction) -> bool {
+        let value = (self.observable)(wave_function);
+        let converged = self.previous.is_some_and(|previous| (value - previous).abs() < self.tolerance);
+        self.previous = Some(value);
+
+        converged
+    }
+
+    fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+/// Stops the propagation when the probability in the analysis region drops below `epsilon`,
+/// e.g. when the wave packet left the interaction region.
+#[derive(Clone)]
+pub struct RegionDensity {
+    mask: ArrayD<f64>,
+    epsilon: f64,
+}
+
+impl RegionDensity {
+    /// Creates new `RegionDensity` on the `grids` with the analysis region given by the `region` closure
+    /// that takes the coordinates of a grid point in the order of the dimensions.
+    pub fn new<F>(grids: &[Grid], region: F, epsilon: f64) -> Self
+    where
+        F: Fn(&[f64]) -> bool,
+    {
+        assert!(epsilon >= 0.0, "Epsilon cannot be negative");
+        let mask = region_mask(grids, |x| if region(x) { 1.0 } else { 0.0 }).map(|x| x.re);
+
+        RegionDensity { mask, epsilon }
+    }
+
+    /// Returns the probability of the `wave_function` in the analysis region.
+    pub fn probability(&self, wave_function: &mut WaveFunction) -> f64 {
+        let density = wave_function.weighted_density();
+        assert!(
+            density.shape() == self.mask.shape(),
+            "Wave function shape has to match the shape of the analysis region"
+        );
+
+        (density * &self.mask).sum()
+    }
+}
+
+impl StopCondition for RegionDensity {
+    fn name(&self) -> &str {
+        "RegionDensity"
+    }
+
+    fn check(&mut self, _time: f64, wave_function: &mut WaveFunction) -> bool {
+        self.probability(wave_function) < self.epsilon
+    }
+}
+
+/// Stops the propagation when the wall time since its start exceeds the `budget`.
+#[derive(Clone)]
+pub struct WallClock {
+    budget: Duration,
+    start: Instant,
+}
+
+impl WallClock {
+    /// Creates new `WallClock` with given time `budget`.
+    pub fn new(budget: Duration) -> Self {
+        WallClock {
+            budget,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl StopCondition for WallClock {
+    fn name(&self) -> &str {
+        "WallClock"
+    }
+
+    fn check(&mut self, _time: f64, _wave_function: &mut WaveFunction) -> bool {
+        self.start.elapsed() >= self.budget
+    }
+
+    fn reset(&mut self) {
+        self.start = Instant::now();
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    any::Any,
+    fs::File,
+    io::Write,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use ndarray::ArrayD;
+use ndarray_npy::write_npy;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::{propagation::Propagation, report::PropagationReport};
+
+/// Returns all pairs of the `first` and `second` parameters, with the `second` parameter changing fastest.
+pub fn cartesian_product<A: Clone, B: Clone>(first: &[A], second: &[B]) -> Vec<(A, B)> {
+    first
+        .iter()
+        .flat_map(|a| second.iter().map(move |b| (a.clone(), b.clone())))
+        .collect()
+}
+
+/// Result of the successful propagation of a sweep member.
+#[derive(Clone, Debug)]
+pub struct MemberResult {
+    pub report: PropagationReport,
+    pub outputs: Vec<(String, ArrayD<f64>)>,
+}
+
+/// Member of the sweep with its `index` in the parameter space,
+/// `result` contains the panic message if the member failed.
+#[derive(Clone, Debug)]
+pub struct SweepMember<P> {
+    pub index: usize,
+    pub parameter: P,
+    pub result: Result<MemberResult, String>,
+}
+
+/// Runs the same propagation for each parameter of the parameter space in parallel,
+/// using at most `threads` threads for all members together.
+#[derive(Clone)]
+pub struct Sweep<P> {
+    parameters: Vec<P>,
+    threads: usize,
+}
+
+impl<P> Sweep<P>
+where
+    P: Clone + Send + Sync,
+{
+    /// Creates new `Sweep` over the `parameters` using all available threads.
+    pub fn new(parameters: Vec<P>) -> Self {
+        Sweep {
+            parameters,
+            threads: rayon::current_num_threads(),
+        }
+    }
+
+    /// Sets the number of threads used by the sweep, including the threads used inside the propagations.
+    pub fn set_threads(&mut self, threads: usize) {
+        assert!(threads > 0, "Number of threads has to be positive");
+
+        self.threads = threads;
+    }
+
+    /// Returns the parameters of the sweep.
+    pub fn parameters(&self) -> &[P] {
+        &self.parameters
+    }
+
+    /// Propagates the `Propagation` created by the `propagation` closure for each parameter
+    /// and collects the reports and saver outputs into [`SweepDataset`].
+    /// Panic of a member is recorded in the dataset and does not abort the other members.
+    pub fn run<F>(&self, propagation: F) -> SweepDataset<P>
+    where
+        F: Fn(&P) -> Propagation + Sync,
+    {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.threads)
+            .build()
+            .expect("Failed to create thread pool");
+
+        let members = pool.install(|| {
+            self.parameters
+                .par_iter()
+                .enumerate()
+                .map(|(index, parameter)| {
+                    let result = catch_unwind(AssertUnwindSafe(|| {
+                        let mut propagation = propagation(parameter);
+                        let report = propagation.propagate();
+
+                        MemberResult {
+                            report,
+                            outputs: propagation.saver_outputs(),
+                        }
+                    }));
+
+                    SweepMember {
+                        index,
+                        parameter: parameter.clone(),
+                        result: result.map_err(panic_message),
+                    }
+                })
+                .collect()
+        });
+
+        SweepDataset { members }
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Unknown panic".to_string()
+    }
+}
+
+#[derive(Serialize)]
+struct IndexEntry<'a, P> {
+    index: usize,
+    parameter: &'a P,
+    report: Option<&'a PropagationReport>,
+    error: Option<&'a str>,
+    outputs: Vec<String>,
+}
+
+/// Results of all members of the [`Sweep`] in the order of the parameter space.
+#[derive(Clone, Debug)]
+pub struct SweepDataset<P> {
+    members: Vec<SweepMember<P>>,
+}
+
+impl<P> SweepDataset<P> {
+    /// Returns all members of the sweep.
+    pub fn members(&self) -> &[SweepMember<P>] {
+        &self.members
+    }
+
+    /// Returns parameters and results of the members that finished successfully.
+    pub fn successful(&self) -> impl Iterator<Item = (&P, &MemberResult)> {
+        self.members
+            .iter()
+            .filter_map(|member| member.result.as_ref().ok().map(|result| (&member.parameter, result)))
+    }
+
+    /// Returns parameters and panic messages of the members that failed.
+    pub fn failed(&self) -> impl Iterator<Item = (&P, &str)> {
+        self.members
+            .iter()
+            .filter_map(|member| member.result.as_ref().err().map(|error| (&member.parameter, error.as_str())))
+    }
+
+    /// Returns the named output of each member, `None` for failed members or members without that output.
+    pub fn output(&self, name: &str) -> Vec<Option<&ArrayD<f64>>> {
+        self.members
+            .iter()
+            .map(|member| {
+                member.result.as_ref().ok().and_then(|result| {
+                    result.outputs.iter().find(|(output, _)| output == name).map(|(_, data)| data)
+                })
+            })
+            .collect()
+    }
+}
+
+impl<P: Serialize> SweepDataset<P> {
+    /// Returns JSON index of the dataset with the parameter, report or error and output file names of each member.
+    pub fn index_json(&self, name: &str) -> String {
+        let entries: Vec<IndexEntry<P>> = self.members
+            .iter()
+            .map(|member| IndexEntry {
+                index: member.index,
+                parameter: &member.parameter,
+                report: member.result.as_ref().ok().map(|result| &result.report),
+                error: member.result.as_ref().err().map(|error| error.as_str()),
+                outputs: member.result
+                    .as_ref()
+                    .map(|result| {
+                        result.outputs
+                            .iter()
+                            .map(|(output, _)| format!("{name}_{}_{output}.npy", member.index))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&entries).unwrap()
+    }
+
+    /// Saves outputs of each member as npy files and the index of the dataset as JSON file with given name in current directory.
+    pub fn save(&self, name: &str) -> Result<(), &str> {
+        let path = std::env::current_dir()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        for member in &self.members {
+            let Ok(result) = &member.result else {
+                continue;
+            };
+
+            for (output, data) in &result.outputs {
+                let saved = write_npy(format!("{path}/{name}_{}_{output}.npy", member.index), data);
+                if saved.is_err() {
+                    return Err("Failed to save sweep output");
+                }
+            }
+        }
+
+        let file = File::create(format!("{path}/{name}_index.json"));
+        let Ok(mut file) = file else {
+            return Err("Failed to create sweep index file");
+        };
+
+        if file.write_all(self.index_json(name).as_bytes()).is_err() {
+            return Err("Failed to save sweep index");
+        }
+
+        Ok(())
+    }
+}
@@ -1,18 +1,40 @@
 use num::complex::Complex64;
+use serde::{Deserialize, Serialize};
 
 /// Time grid for the propagation of the wave function.
-/// - `step` is the time in au for each step.
+/// - `step` is the time in au for each step, negative for backward propagation.
 /// - `step_no` is the number of steps in the propagation.
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct TimeGrid {
     pub step: f64,
     pub step_no: usize,
     pub im_time: bool,
 }
 
+impl TimeGrid {
+    /// Returns time grid with reversed direction of the time step.
+    pub fn reversed(&self) -> TimeGrid {
+        TimeGrid {
+            step: -self.step,
+            ..self.clone()
+        }
+    }
+
+    /// Returns whether the time grid propagates backward in time.
+    pub fn is_backward(&self) -> bool {
+        self.step < 0.0
+    }
+
+    /// Returns the total propagation time of the time grid.
+    pub fn duration(&self) -> f64 {
+        self.step * self.step_no as f64
+    }
+}
+
 /// Enum for the type of step in the split-operator method. Available options are:
 /// - `Full` for a full step.
 /// - `Half` for a half step.
+#[derive(Clone, Copy, Debug)]
 pub enum TimeStep {
     Full,
     Half,
@@ -31,3 +53,61 @@ pub fn select_step(step: TimeStep, time: &TimeGrid) -> Complex64 {
         Complex64::from(time_step)
     }
 }
+
+/// Non uniform time grid given by consecutive uniform [`TimeGrid`] segments,
+/// e.g. fine steps during a pulse and coarse steps afterwards.
+#[derive(Clone, Default)]
+pub struct TimeSchedule {
+    segments: Vec<TimeGrid>,
+}
+
+impl TimeSchedule {
+    /// Creates new schedule from consecutive `segments`.
+    pub fn new(segments: Vec<TimeGrid>) -> Self {
+        assert!(!segments.is_empty(), "Time schedule has to have at least one segment");
+
+        Self { segments }
+    }
+
+    /// Creates new schedule from the list of step sizes, consecutive equal steps are grouped into single segment.
+    pub fn from_steps(steps: &[f64], im_time: bool) -> Self {
+        let mut segments: Vec<TimeGrid> = Vec::new();
+
+        for &step in steps {
+            match segments.last_mut() {
+                Some(segment) if segment.step == step => segment.step_no += 1,
+                _ => segments.push(TimeGrid { step, step_no: 1, im_time }),
+            }
+        }
+
+        Self::new(segments)
+    }
+
+    /// Returns uniform segments of the schedule.
+    pub fn segments(&self) -> &[TimeGrid] {
+        &self.segments
+    }
+
+    /// Returns the total number of steps.
+    pub fn step_no(&self) -> usize {
+        self.segments.iter().map(|segment| segment.step_no).sum()
+    }
+
+    /// Returns the total propagation time.
+    pub fn duration(&self) -> f64 {
+        self.segments.iter().map(|segment| segment.duration()).sum()
+    }
+
+    /// Returns schedule propagating backward through the segments in reversed order.
+    pub fn reversed(&self) -> Self {
+        Self {
+            segments: self.segments.iter().rev().map(|segment| segment.reversed()).collect(),
+        }
+    }
+}
+
+impl From<TimeGrid> for TimeSchedule {
+    fn from(time_grid: TimeGrid) -> Self {
+        Self::new(vec![time_grid])
+    }
+}
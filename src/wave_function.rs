@@ -6,13 +6,14 @@ use crate::grid::Grid;
 
 /// Struct to hold information about a wave function on actual time step.
 /// It contains the wave function array in the representation of grids.
+/// The array and grids are modified only through accessors that invalidate the cached norm,
 /// `change_observer` is used to observe possible norm and grid changes during the propagation.
 #[derive(Clone, Default)]
 pub struct WaveFunction {
-    pub array: ArrayD<Complex64>,
-    pub grids: Vec<Grid>,
+    array: ArrayD<Complex64>,
+    grids: Vec<Grid>,
 
-    pub change_observer: ChangeObserver,
+    change_observer: ChangeObserver,
 
     /// Array of weights for calculating wave function norm.
     weight_amplitude_array: ArrayD<Complex64>,
@@ -46,6 +47,47 @@ impl WaveFunction {
         }
     }
 
+    /// Returns the wave function array in the representation of actual `grids`.
+    pub fn array(&self) -> &ArrayD<Complex64> {
+        &self.array
+    }
+
+    /// Returns mutable wave function array and invalidates the cached norm.
+    pub fn array_mut(&mut self) -> &mut ArrayD<Complex64> {
+        self.change_observer.invalidate_norm();
+
+        &mut self.array
+    }
+
+    /// Returns actual grids of the wave function.
+    pub fn grids(&self) -> &[Grid] {
+        &self.grids
+    }
+
+    /// Returns mutable grids of the wave function and invalidates the cached norm and weights of the grids.
+    pub fn grids_mut(&mut self) -> &mut Vec<Grid> {
+        self.change_observer.invalidate_norm();
+        self.change_observer.invalidate_grids();
+
+        &mut self.grids
+    }
+
+    /// Swaps the grid of the wave function on dimension `dimension_no` with given `grid`, e.g. during basis transformation.
+    pub fn swap_grid(&mut self, dimension_no: usize, grid: &mut Grid) {
+        self.change_observer.invalidate_norm();
+        self.change_observer.invalidate_grid(dimension_no);
+
+        self.grids[dimension_no].swap(grid);
+    }
+
+    /// Updates the weight amplitude array if the grids or the shape of the array have changed.
+    fn observe_grids(&mut self) {
+        if self.change_observer.has_grid_changed(&self.grids) || self.weight_amplitude_array.shape() != self.array.shape() {
+            self.update_weight_amplitude_array();
+            self.change_observer.observe_grid(&self.grids);
+        }
+    }
+
     /// Updates the weight amplitude array from current grids.
     fn update_weight_amplitude_array(&mut self) {
         self.weight_amplitude_array = Array::ones(self.array.dim());
@@ -66,14 +108,11 @@ impl WaveFunction {
 
     /// Returns the norm of the wave function.
     pub fn norm(&mut self) -> f64 {
-        if self.change_observer.possible_norm_change == false {
+        if !self.change_observer.possible_norm_change() && !self.change_observer.has_grid_changed(&self.grids) {
             return self.change_observer.last_norm();
         }
 
-        if self.change_observer.has_grid_changed(&self.grids) {
-            self.update_weight_amplitude_array();
-            self.change_observer.observe_grid(&self.grids);
-        }
+        self.observe_grids();
 
         let norm = Zip::from(&self.array)
             .and(&self.weight_amplitude_array)
@@ -100,6 +139,18 @@ impl WaveFunction {
         dot_prod / (norm_1 * norm_2).sqrt()
     }
 
+    /// Returns the inner product ⟨self|other⟩ integrated with the weights of actual `grids`.
+    pub fn inner_product(&mut self, other: &WaveFunction) -> Complex64 {
+        assert!(self.array.shape() == other.array.shape(), "Wave functions have to have the same shape");
+
+        self.observe_grids();
+
+        Zip::from(&self.array)
+            .and(&other.array)
+            .and(&self.weight_amplitude_array)
+            .fold(Complex64::new(0.0, 0.0), |acc, x, y, w| acc + x.conj() * y * w.norm_sqr())
+    }
+
     /// Sets the norm of the wave function to `new_norm`.
     pub fn normalize(&mut self, new_norm: f64) {
         let norm = self.norm();
@@ -117,12 +168,19 @@ impl WaveFunction {
         density
     }
 
+    /// Returns the density of the wave function on actual `grids` multiplied by the integration weights,
+    /// so that its sum is equal to the norm of the wave function.
+    pub fn weighted_density(&mut self) -> ArrayD<f64> {
+        self.observe_grids();
+
+        Zip::from(&self.array)
+            .and(&self.weight_amplitude_array)
+            .map_collect(|x, w| x.norm_sqr() * w.norm_sqr())
+    }
+
     /// Return the density of the wave function on actual `grids` along given `axis`.
     pub fn state_density(&mut self, axis: usize) -> Array1<f64> {
-        if self.change_observer.has_grid_changed(&self.grids) {
-            self.update_weight_amplitude_array();
-            self.change_observer.observe_grid(&self.grids);
-        }
+        self.observe_grids();
 
         let mut density = self.density();
 
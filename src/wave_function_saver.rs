@@ -1,15 +1,19 @@
-use ndarray::{s, Array, Array1, Array2, Array3};
+use ndarray::{s, Array, Array1, Array2, Array3, ArrayD};
 use ndarray_npy::write_npy;
 
-use crate::{grid::Grid, saver::Saver, time_grid::TimeGrid, wave_function::WaveFunction};
+use crate::{
+    grid::Grid,
+    saver::{FrameSampler, Saver},
+    time_grid::TimeGrid,
+    wave_function::WaveFunction,
+};
 
-/// Saves density of a wave function that is in 2d space during propagation.
+/// Saves density of a wave function that is in 2d space during propagation
+/// at `frames_no` frames evenly spaced in time over the [`TimeGrid`] or given duration.
 #[derive(Clone)]
 pub struct WaveFunctionSaver {
     name: String,
-    current_frame: usize,
-    frames_no: usize,
-    time_grid: TimeGrid,
+    sampler: FrameSampler,
     x_grid: Grid,
     y_grid: Grid,
     data_array: Array3<f64>,
@@ -24,12 +28,22 @@ impl WaveFunctionSaver {
         x_grid: &Grid,
         y_grid: &Grid,
         frames_no: usize,
+    ) -> WaveFunctionSaver {
+        WaveFunctionSaver::with_duration(name, time_grid.duration(), x_grid, y_grid, frames_no)
+    }
+
+    /// Creates new `WaveFunctionSaver` with frames spread over the propagation lasting `duration`,
+    /// e.g. the duration of a [`TimeSchedule`](crate::time_grid::TimeSchedule).
+    pub fn with_duration(
+        name: String,
+        duration: f64,
+        x_grid: &Grid,
+        y_grid: &Grid,
+        frames_no: usize,
     ) -> WaveFunctionSaver {
         WaveFunctionSaver {
             name,
-            current_frame: 0,
-            frames_no,
-            time_grid: time_grid.clone(),
+            sampler: FrameSampler::new(duration, frames_no),
             x_grid: x_grid.clone(),
             y_grid: y_grid.clone(),
             data_array: Array::zeros((x_grid.nodes_no, y_grid.nodes_no, frames_no)),
@@ -39,14 +53,16 @@ impl WaveFunctionSaver {
 }
 
 impl Saver for WaveFunctionSaver {
-    fn monitor(&mut self, wave_function: &mut WaveFunction) {
-        if wave_function.array.ndim() != 2 {
+    fn name(&self) -> &str {
+        "WaveFunctionSaver"
+    }
+
+    fn monitor(&mut self, wave_function: &mut WaveFunction, time: f64) {
+        if wave_function.array().ndim() != 2 {
             panic!("Wave function must be 2d for now");
         }
 
-        let frequency = self.time_grid.step_no / self.frames_no;
-
-        if self.current_frame % frequency == 0 && self.current_frame / frequency < self.frames_no {
+        if let Some(frame) = self.sampler.sample(time) {
             let density = wave_function.density();
 
             let density2d: Array2<f64> = density
@@ -54,13 +70,11 @@ impl Saver for WaveFunctionSaver {
                 .unwrap();
 
             self.data_array
-                .slice_mut(s![.., .., self.current_frame / frequency])
+                .slice_mut(s![.., .., frame])
                 .assign(&density2d);
 
-            self.times.push(self.time_grid.step * (self.current_frame as f64 + 1.))
+            self.times.push(time);
         }
-
-        self.current_frame += 1;
     }
 
     fn save(&self) -> Result<(), &str> {
@@ -96,18 +110,37 @@ impl Saver for WaveFunctionSaver {
         Ok(())
     }
 
+    fn outputs(&self) -> Vec<(String, ArrayD<f64>)> {
+        vec![
+            (self.name.clone(), self.data_array.clone().into_dyn()),
+            (format!("{}_x_grid", self.name), Array::from_vec(self.x_grid.nodes.clone()).into_dyn()),
+            (format!("{}_y_grid", self.name), Array::from_vec(self.y_grid.nodes.clone()).into_dyn()),
+            (format!("{}_time", self.name), Array::from_vec(self.times.clone()).into_dyn()),
+        ]
+    }
+
+    fn output_names(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            format!("{}_x_grid", self.name),
+            format!("{}_y_grid", self.name),
+            format!("{}_time", self.name),
+        ]
+    }
+
     fn reset(&mut self) {
-        self.data_array = Array::zeros((self.x_grid.nodes_no, self.y_grid.nodes_no, self.frames_no))
+        self.data_array = Array::zeros((self.x_grid.nodes_no, self.y_grid.nodes_no, self.sampler.frames_no()));
+        self.times.clear();
+        self.sampler.reset();
     }
 }
 
-/// Saves density of a wave function on given dimension during propagation.
+/// Saves density of a wave function on given dimension during propagation
+/// at `frames_no` frames evenly spaced in time over the [`TimeGrid`] or given duration.
 #[derive(Clone)]
 pub struct StateSaver {
     name: String,
-    current_frame: usize,
-    frames_no: usize,
-    time_grid: TimeGrid,
+    sampler: FrameSampler,
     state_grid: Grid,
     data_array: Array2<f64>,
     times: Vec<f64>
@@ -120,12 +153,21 @@ impl StateSaver {
         time_grid: &TimeGrid,
         state_grid: &Grid,
         frames_no: usize,
+    ) -> StateSaver {
+        StateSaver::with_duration(name, time_grid.duration(), state_grid, frames_no)
+    }
+
+    /// Creates new `StateSaver` with frames spread over the propagation lasting `duration`,
+    /// e.g. the duration of a [`TimeSchedule`](crate::time_grid::TimeSchedule).
+    pub fn with_duration(
+        name: String,
+        duration: f64,
+        state_grid: &Grid,
+        frames_no: usize,
     ) -> StateSaver {
         StateSaver {
             name,
-            current_frame: 0,
-            frames_no,
-            time_grid: time_grid.clone(),
+            sampler: FrameSampler::new(duration, frames_no),
             state_grid: state_grid.clone(),
             data_array: Array::zeros((state_grid.nodes_no, frames_no)),
             times: Vec::with_capacity(frames_no)
@@ -134,20 +176,20 @@ impl StateSaver {
 }
 
 impl Saver for StateSaver {
-    fn monitor(&mut self, wave_function: &mut WaveFunction) {
-        let frequency = self.time_grid.step_no / self.frames_no;
+    fn name(&self) -> &str {
+        "StateSaver"
+    }
 
-        if self.current_frame % frequency == 0 && self.current_frame / frequency < self.frames_no {
+    fn monitor(&mut self, wave_function: &mut WaveFunction, time: f64) {
+        if let Some(frame) = self.sampler.sample(time) {
             let state = wave_function.state_density(self.state_grid.dimension_no);
 
             self.data_array
-                .slice_mut(s![.., self.current_frame / frequency])
+                .slice_mut(s![.., frame])
                 .assign(&state);
 
-            self.times.push(self.time_grid.step * (self.current_frame as f64 + 1.));
+            self.times.push(time);
         }
-
-        self.current_frame += 1;
     }
 
     fn save(&self) -> Result<(), &str> {
@@ -177,7 +219,28 @@ impl Saver for StateSaver {
         Ok(())
     }
 
+    fn outputs(&self) -> Vec<(String, ArrayD<f64>)> {
+        vec![
+            (self.name.clone(), self.data_array.clone().into_dyn()),
+            (
+                format!("{}_{}_grid", self.name, self.state_grid.name),
+                Array::from_vec(self.state_grid.nodes.clone()).into_dyn(),
+            ),
+            (format!("{}_time", self.name), Array::from_vec(self.times.clone()).into_dyn()),
+        ]
+    }
+
+    fn output_names(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            format!("{}_{}_grid", self.name, self.state_grid.name),
+            format!("{}_time", self.name),
+        ]
+    }
+
     fn reset(&mut self) {
-        self.data_array = Array::zeros((self.state_grid.nodes_no, self.frames_no));
+        self.data_array = Array::zeros((self.state_grid.nodes_no, self.sampler.frames_no()));
+        self.times.clear();
+        self.sampler.reset();
     }
 }
@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod absorbing_tests {
+    use ndarray::{Array1, ArrayD, IxDyn};
+    use num::complex::Complex64;
+    use split_operator::{
+        border_dumping::{dumping_both, dumping_end, region_mask, BorderDumping},
+        control::Control,
+        grid::Grid,
+        hamiltonian::{Hamiltonian, HamiltonianTerm},
+        loss_checker::LossChecker,
+        loss_distribution::LossDistribution,
+        hamiltonian_factory::absorbing_potentials::{AbsorbingForm, AbsorbingPotential, Boundary},
+        propagator::{propagator_factory::absorbing_into_propagator, Propagator},
+        time_grid::{TimeGrid, TimeSchedule, TimeStep},
+        wave_function::WaveFunction,
+    };
+
+    #[test]
+    fn absorbing_layers() {
+        let grid = Grid::new_linear_continuos("space", 0.0, 100.0, 1001, 0);
+        let mass = 1000.0;
+        let min_energy = 1e-3;
+
+        let forms = [
+            AbsorbingForm::Polynomial { order: 2, width: 20.0 },
+            AbsorbingForm::Polynomial { order: 4, width: 20.0 },
+            AbsorbingForm::Manolopoulos,
+            AbsorbingForm::RissMeyer { order: 2 },
+        ];
+
+        for form in forms {
+            let absorbing = AbsorbingPotential::new(form, Boundary::Both, min_energy, mass);
+            let width = absorbing.width();
+            let potential = absorbing.potential(&grid);
+
+            for (x, w) in grid.nodes.iter().zip(potential.iter()) {
+                assert!(*w >= 0.0 && w.is_finite());
+                if *x > width && *x < 100.0 - width {
+                    assert_eq!(*w, 0.0);
+                }
+            }
+            assert!(potential[0] > 0.0 && potential[1000] > 0.0);
+
+            for im_time in [false, true] {
+                let time_grid = TimeGrid { step: 10.0, step_no: 1, im_time };
+                let mut propagator = absorbing_into_propagator(potential.clone(), &grid, &time_grid, TimeStep::Half);
+
+                let array = ArrayD::<Complex64>::ones(IxDyn(&[grid.nodes_no]));
+                let mut wave_function = WaveFunction::new(array, vec![grid.clone()]);
+                propagator.apply(&mut wave_function, 0.0);
+
+                assert_eq!(wave_function.array()[500], Complex64::from(1.0));
+                assert!(wave_function.array()[0].norm() < 1.0 && wave_function.array()[1000].norm() < 1.0);
+                assert!(wave_function.array().iter().all(|x| x.norm() <= 1.0));
+            }
+        }
+    }
+
+    #[test]
+    fn manolopoulos_energy_statistics() {
+        let grid = Grid::new_linear_continuos("space", 0.0, 100.0, 1001, 0);
+        let absorbing = AbsorbingPotential::new(AbsorbingForm::Manolopoulos, Boundary::End, 1e-3, 1000.0);
+        let potential: Array1<f64> = grid.nodes.iter().map(|x| 1e-4 * (x - 50.0).powi(2)).collect();
+
+        let mut hamiltonian = Hamiltonian::new();
+        hamiltonian.add_term(HamiltonianTerm::one_dim("potential", potential, &grid));
+        hamiltonian.add_term(HamiltonianTerm::absorbing("absorbing", absorbing.potential(&grid), &grid));
+
+        let array = ArrayD::<Complex64>::ones(IxDyn(&[grid.nodes_no]));
+        let wave_function = WaveFunction::new(array, vec![grid.clone()]);
+
+        let h_state = hamiltonian.apply(&wave_function);
+        assert!(h_state.array().iter().all(|x| x.re.is_finite() && x.im.is_finite()));
+
+        let statistics = hamiltonian.energy_statistics(&wave_function);
+        assert!(statistics.mean.is_finite() && statistics.mean > 0.0);
+        assert!(statistics.variance.is_finite());
+        assert!(statistics.residual.is_finite());
+    }
+
+    #[test]
+    fn region_losses() {
+        let x_grid = Grid::new_linear_continuos("x", 0.0, 10.0, 101, 0);
+        let y_grid = Grid::new_linear_continuos("y", 0.0, 10.0, 51, 1);
+        let grids = vec![x_grid.clone(), y_grid.clone()];
+
+        let array = ArrayD::<Complex64>::ones(IxDyn(&[101, 51]));
+        let mut wave_function = WaveFunction::new(array, grids.clone());
+        let initial_norm = wave_function.norm();
+
+        let mut dumping = BorderDumping::empty();
+        dumping.add_mask("x both", dumping_both(2.0, 0.5, &x_grid), &x_grid);
+        dumping.add_mask("y end", dumping_end(2.0, 0.5, &y_grid), &y_grid);
+        dumping.add_n_dim_mask("center", region_mask(&grids, |x| if x[0] > 4.0 && x[0] < 6.0 && x[1] < 1.0 { 0.5 } else { 1.0 }));
+        dumping.add_loss_checker(LossChecker::new("total"));
+
+        dumping.first_half(&mut wave_function, 0.0);
+
+        let region_losses = dumping.region_losses();
+        assert_eq!(region_losses.len(), 3);
+        assert!(region_losses.iter().all(|loss| loss.loss > 0.0));
+
+        let total: f64 = region_losses.iter().map(|loss| loss.loss).sum();
+        assert!((total - dumping.loss().as_ref().unwrap().cumulative_loss()).abs() < 1e-12);
+        assert!((initial_norm - wave_function.norm() - total).abs() < 1e-12);
+    }
+
+    #[test]
+    fn loss_distribution() {
+        let x_grid = Grid::new_linear_continuos("x", 0.0, 10.0, 101, 0);
+        let l_grid = Grid::new_linear_countable("l", 0.0, 4.0, 5, 1);
+        let grids = vec![x_grid.clone(), l_grid.clone()];
+
+        let array = ArrayD::<Complex64>::ones(IxDyn(&[101, 5]));
+        let mut wave_function = WaveFunction::new(array, grids.clone());
+
+        let time_grid = TimeGrid { step: 1.0, step_no: 10, im_time: false };
+        let mut loss_checker = LossChecker::new("total");
+        loss_checker.add_distribution("total".to_string(), vec![0, 1], 10, &time_grid);
+
+        let mut dumping = BorderDumping::empty();
+        dumping.add_n_dim_mask("l dependent", region_mask(&grids, |x| if x[0] > 8.0 { 1.0 - 0.1 * x[1] } else { 1.0 }));
+        dumping.add_loss_checker(loss_checker);
+
+        for i in 0..10 {
+            dumping.first_half(&mut wave_function, i as f64);
+            dumping.second_half(&mut wave_function, (i + 1) as f64);
+        }
+
+        let loss_checker = dumping.loss().as_ref().unwrap();
+        let distribution = loss_checker.distribution().as_ref().unwrap();
+
+        let l_projection = distribution.projection(1);
+        assert_eq!(l_projection[0], 0.0);
+        assert!(l_projection.windows(2).into_iter().all(|x| x[0] < x[1]));
+        assert!((l_projection.sum() - loss_checker.cumulative_loss()).abs() < 1e-12);
+
+        let x_projection = distribution.projection(0);
+        assert!(x_projection.iter().take(80).all(|&x| x == 0.0));
+        assert!((x_projection.sum() - loss_checker.cumulative_loss()).abs() < 1e-12);
+
+        let in_time = distribution.projection_in_time(1).unwrap();
+        assert_eq!(in_time.shape(), &[10, 5]);
+        assert_eq!(distribution.times(), (0..10).map(|i| i as f64).collect::<Vec<f64>>());
+    }
+
+    #[test]
+    fn region_loss_distribution() {
+        let x_grid = Grid::new_linear_continuos("x", 0.0, 10.0, 101, 0);
+        let l_grid = Grid::new_linear_countable("l", 0.0, 4.0, 5, 1);
+        let grids = vec![x_grid.clone(), l_grid.clone()];
+
+        let array = ArrayD::<Complex64>::ones(IxDyn(&[101, 5]));
+        let mut wave_function = WaveFunction::new(array, grids.clone());
+
+        let time_grid = TimeGrid { step: 1.0, step_no: 10, im_time: false };
+        let mut dumping = BorderDumping::empty();
+        dumping.add_mask("x start", dumping_both(2.0, 0.5, &x_grid), &x_grid);
+        dumping.add_n_dim_mask("l dependent", region_mask(&grids, |x| if x[0] > 8.0 { 1.0 - 0.1 * x[1] } else { 1.0 }));
+        dumping.region_loss_checker_mut("l dependent")
+            .unwrap()
+            .add_distribution("l dependent".to_string(), vec![1], 5, &time_grid);
+        assert!(dumping.region_loss_checker_mut("missing").is_none());
+
+        for i in 0..10 {
+            dumping.first_half(&mut wave_function, i as f64);
+            dumping.second_half(&mut wave_function, (i + 1) as f64);
+        }
+
+        assert!(dumping.region_loss_checker("x start").unwrap().distribution().is_none());
+
+        let loss_checker = dumping.region_loss_checker("l dependent").unwrap();
+        let distribution = loss_checker.distribution().as_ref().unwrap();
+        assert!((distribution.projection(1).sum() - loss_checker.cumulative_loss()).abs() < 1e-12);
+
+        // sampled in time independently of the number of checks per step
+        assert_eq!(distribution.times(), &[0.0, 2.0, 4.0, 6.0, 8.0]);
+        assert_eq!(distribution.projection_in_time(1).unwrap().shape(), &[5, 5]);
+    }
+
+    #[test]
+    fn schedule_loss_distribution() {
+        let x_grid = Grid::new_linear_continuos("x", 0.0, 10.0, 101, 0);
+        let array = ArrayD::<Complex64>::ones(IxDyn(&[101]));
+        let mut wave_function = WaveFunction::new(array, vec![x_grid.clone()]);
+
+        let schedule = TimeSchedule::from_steps(&[1.0, 1.0, 1.0, 1.0, 2.0, 2.0], false);
+        let mut loss_checker = LossChecker::new("total");
+        loss_checker.set_distribution(LossDistribution::with_duration("total".to_string(), vec![0], 4, schedule.duration()));
+
+        let mut dumping = BorderDumping::empty();
+        dumping.add_mask("x end", dumping_end(2.0, 0.5, &x_grid), &x_grid);
+        dumping.add_loss_checker(loss_checker);
+
+        let mut time = 0.0;
+        for segment in schedule.segments() {
+            for _ in 0..segment.step_no {
+                dumping.first_half(&mut wave_function, time);
+                time += segment.step;
+                dumping.second_half(&mut wave_function, time);
+            }
+        }
+
+        let distribution = dumping.loss().as_ref().unwrap().distribution().as_ref().unwrap();
+        assert_eq!(distribution.times(), &[0.0, 2.0, 4.0, 6.0]);
+    }
+}
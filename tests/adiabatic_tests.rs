@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod adiabatic_tests {
+    use ndarray::{array, Array2, ArrayD, Dimension, IxDyn};
+    use num::complex::Complex64;
+    use split_operator::{
+        grid::Grid,
+        propagator::{
+            adiabatic_transformation::AdiabaticTransformation,
+            propagator_factory::{hermitian_matrix_into_propagator, n_dim_into_propagator},
+            transformation::Transformation,
+            Propagator,
+        },
+        time_grid::{TimeGrid, TimeStep},
+        wave_function::WaveFunction,
+    };
+
+    /// Two crossing diabatic channels with coupling of changing sign.
+    fn potential(r: f64) -> Array2<Complex64> {
+        array![
+            [Complex64::from(r - 2.0), Complex64::new(0.1 * (r - 1.0), 0.05)],
+            [Complex64::new(0.1 * (r - 1.0), -0.05), Complex64::from(2.0 - r)],
+        ]
+    }
+
+    fn test_wave_function(shape: &[usize], grids: Vec<Grid>) -> WaveFunction {
+        let array = ArrayD::from_shape_fn(IxDyn(shape), |i| {
+            let index: usize = i.slice().iter().enumerate().map(|(k, x)| (k + 1) * x).sum();
+            Complex64::new((0.3 * index as f64).cos(), 0.1 * index as f64)
+        });
+
+        WaveFunction::new(array, grids)
+    }
+
+    fn assert_close(a: &ArrayD<Complex64>, b: &ArrayD<Complex64>) {
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).norm() < 1e-10, "{x} != {y}");
+        }
+    }
+
+    #[test]
+    fn test_adiabatic_propagation() {
+        let radial = Grid::new_linear_continuos("r", 0.0, 4.0, 16, 1);
+        let channels = Grid::new_linear_countable("channel", 0.0, 1.0, 2, 0);
+        let other = Grid::new_linear_continuos("x", 0.0, 1.0, 3, 2);
+        let grids = vec![channels.clone(), radial.clone(), other];
+
+        let mut adiabatic = AdiabaticTransformation::new(potential, &radial, &channels, "adiabatic");
+        for row in adiabatic.energies().rows() {
+            assert!(row[0] <= row[1]);
+        }
+
+        let mut wave_function = test_wave_function(&[2, 16, 3], grids);
+        let initial = wave_function.array().clone();
+        let norm = wave_function.norm();
+
+        adiabatic.transform(&mut wave_function);
+        assert_eq!(wave_function.grids()[0].name, "adiabatic");
+        assert!((wave_function.norm() - norm).abs() < 1e-10);
+        adiabatic.inverse_transform(&mut wave_function);
+        assert_eq!(wave_function.grids()[0].name, "channel");
+        assert_close(wave_function.array(), &initial);
+
+        let time = TimeGrid { step: 0.4, step_no: 1, im_time: false };
+        let mut energies = n_dim_into_propagator(adiabatic.adiabatic_potential(&wave_function), &time, TimeStep::Full);
+        let mut coupled = hermitian_matrix_into_propagator(|r| potential(r[0]), &[&radial], 0, &time, TimeStep::Full);
+
+        let mut expected = wave_function.clone();
+        coupled.apply(&mut expected, 0.0);
+
+        adiabatic.transform(&mut wave_function);
+        energies.apply(&mut wave_function, 0.0);
+        adiabatic.inverse_transform(&mut wave_function);
+
+        assert_close(wave_function.array(), expected.array());
+    }
+}
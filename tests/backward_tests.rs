@@ -0,0 +1,126 @@
+mod common;
+
+#[cfg(test)]
+mod backward_tests {
+    use ndarray::Array1;
+    use num::complex::Complex64;
+    use quantum::{
+        particle_factory::create_atom,
+        particles::Particles,
+        units::energy_units::{Energy, Kelvin},
+    };
+    use split_operator::{
+        border_dumping::{dumping_both, BorderDumping},
+        control::Apply,
+        hamiltonian_factory::{analytic_potentials::harmonic, kinetic_operator::kinetic_hamiltonian},
+        loss_checker::LossChecker,
+        propagation::{OperationStack, Propagation},
+        propagator::{
+            fft_transformation::FFTTransformation, one_dim_propagator::OneDimPropagator,
+            propagator_factory::one_dim_into_propagator, transformation::Order,
+        },
+        time_grid::{TimeGrid, TimeStep},
+    };
+
+    use crate::common::{harmonic_grid, wave_packet};
+
+    /// Propagation of the Li6 - Li7 wave packet with given `momentum` in the harmonic trap
+    /// after the operations already added to `operation_stack`.
+    fn trap_propagation(momentum: f64, time_grid: &TimeGrid, mut operation_stack: OperationStack) -> Propagation {
+        let grid = harmonic_grid();
+        let collision_params = Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        );
+        let potential: Array1<f64> = grid.nodes
+            .iter()
+            .map(|&x| harmonic(x, 0.0, collision_params.red_mass(), 0.001))
+            .collect();
+        let kinetic = kinetic_hamiltonian(&grid, &collision_params);
+
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(potential, &grid, time_grid, TimeStep::Half)));
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grid, "momentum")), Order::Normal);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(kinetic, &grid, time_grid, TimeStep::Full)));
+
+        Propagation::new(wave_packet(momentum), time_grid.clone(), operation_stack)
+    }
+
+    #[test]
+    fn test_forward_backward() {
+        let mut propagation = trap_propagation(2.0, &TimeGrid { step: 50.0, step_no: 300, im_time: false }, OperationStack::new());
+        let initial = propagation.wave_function().clone();
+
+        propagation.propagate();
+        let propagated = propagation.wave_function().clone();
+        let difference = propagated.array().iter()
+            .zip(initial.array().iter())
+            .map(|(x, y)| (x - y).norm())
+            .fold(0.0, f64::max);
+        assert!(difference > 1e-2);
+
+        propagation.propagate_backward();
+        for (x, y) in propagation.wave_function().array().iter().zip(initial.array().iter()) {
+            assert!((x - y).norm() < 1e-12);
+        }
+        assert!(!propagation.time_grid().is_backward());
+    }
+
+    #[test]
+    fn test_round_trip_error() {
+        let mut propagation = trap_propagation(2.0, &TimeGrid { step: 50.0, step_no: 300, im_time: false }, OperationStack::new());
+        let initial = propagation.wave_function().clone();
+
+        let error = propagation.round_trip_error();
+        assert!(error < 1e-12);
+        assert!(propagation.wave_function().array() == initial.array());
+    }
+
+    #[test]
+    fn test_round_trip_error_keeps_losses() {
+        let grid = harmonic_grid();
+        let mut dumping = BorderDumping::empty();
+        dumping.add_mask("x both", dumping_both(1.0, 0.5, &grid), &grid);
+        dumping.add_loss_checker(LossChecker::new("dumping"));
+
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_control(Box::new(dumping), Apply::FirstHalf | Apply::SecondHalf);
+        operation_stack.enable_profiling();
+
+        let time_grid = TimeGrid { step: 50.0, step_no: 100, im_time: false };
+        let mut propagation = trap_propagation(20.0, &time_grid, operation_stack);
+        let report = propagation.propagate();
+        let losses = propagation.get_losses();
+        let calls: Vec<usize> = propagation.profiles().unwrap().iter().map(|x| x.calls).collect();
+        assert!(losses[0] > 0.0);
+
+        propagation.round_trip_error();
+        assert_eq!(propagation.get_losses(), losses);
+        assert_eq!(propagation.profiles().unwrap().iter().map(|x| x.calls).collect::<Vec<usize>>(), calls);
+
+        // report of the current state without performing any step
+        propagation.set_time_grid(TimeGrid { step_no: 0, ..time_grid });
+        let current = propagation.propagate();
+        assert_eq!(current.operations[0].region_losses[0].loss, report.operations[0].region_losses[0].loss);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not follow the time grid change")]
+    fn test_explicit_operator_backward() {
+        let grid = harmonic_grid();
+        let mut propagator = OneDimPropagator::new(grid.nodes_no, grid.dimension_no);
+        propagator.set_operator(Array1::from_elem(grid.nodes_no, Complex64::from(1.0)));
+
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_propagator(Box::new(propagator));
+
+        let time_grid = TimeGrid { step: 50.0, step_no: 10, im_time: false };
+        trap_propagation(2.0, &time_grid, operation_stack).propagate_backward();
+    }
+
+    #[test]
+    #[should_panic(expected = "Backward propagation is possible only in real time")]
+    fn test_imaginary_time_backward() {
+        trap_propagation(2.0, &TimeGrid { step: 50.0, step_no: 300, im_time: true }, OperationStack::new()).propagate_backward();
+    }
+}
@@ -0,0 +1,20 @@
+use ndarray::{ArrayD, IxDyn};
+use split_operator::{
+    grid::Grid,
+    wave_function::{gaussian_distribution, WaveFunction},
+};
+
+/// Grid of the harmonic trap used in the propagation tests.
+pub fn harmonic_grid() -> Grid {
+    Grid::new_linear_continuos("x", -4.0, 4.0, 128, 0)
+}
+
+/// Returns normalized wave packet on [`harmonic_grid`] centered at x = 1 with width 0.3 and given `momentum`.
+pub fn wave_packet(momentum: f64) -> WaveFunction {
+    let grid = harmonic_grid();
+    let array = ArrayD::from_shape_fn(IxDyn(&[grid.nodes_no]), |i| gaussian_distribution(grid.nodes[i[0]], 1.0, 0.3, momentum));
+    let mut wave_function = WaveFunction::new(array, vec![grid]);
+    wave_function.normalize(1.0);
+
+    wave_function
+}
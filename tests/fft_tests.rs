@@ -1,13 +1,35 @@
 #[cfg(test)]
 mod fft_tests {
-    use ndarray::arr2;
+    use ndarray::{arr2, Array1, Array3, ArrayD, Axis, Ix3, IxDyn, ShapeBuilder};
     use num::complex::Complex64;
+    use rustfft::FftPlanner;
     use split_operator::{
         grid::Grid,
-        propagator::{transformation::Transformation, fft_transformation::FFTTransformation},
+        hamiltonian_factory::kinetic_operator::n_dim_kinetic_hamiltonian,
+        propagator::{
+            fft_transformation::FFTTransformation, multi_fft_transformation::MultiFFTTransformation,
+            one_dim_propagator::OneDimPropagator,
+            transformation::Transformation, Propagator,
+        },
         wave_function::WaveFunction,
     };
 
+    fn naive_fft(array: &ArrayD<Complex64>, axis: usize) -> ArrayD<Complex64> {
+        let size = array.shape()[axis];
+        let fft = FftPlanner::new().plan_fft_forward(size);
+        let mut result = array.clone();
+
+        for mut lane in result.lanes_mut(Axis(axis)) {
+            let mut temp = lane.to_vec();
+            fft.process(&mut temp);
+            lane.iter_mut()
+                .zip(temp.iter())
+                .for_each(|(dest, src)| *dest = *src / (size as f64).sqrt());
+        }
+
+        result
+    }
+
     #[test]
     fn test_fft_diagonalization() {
         let grid1 = Grid::new_linear_countable("a", 0.0, 1.0, 4, 0);
@@ -43,7 +65,7 @@ mod fft_tests {
         let mut fft_diag = FFTTransformation::new(&grid1, "a_fft");
 
         fft_diag.transform(&mut wf);
-        let wf_array_transformed = wf.array.clone();
+        let wf_array_transformed = wf.array().clone();
         fft_diag.inverse_transform(&mut wf);
 
         let transformed_array = arr2(&[
@@ -73,7 +95,7 @@ mod fft_tests {
             ],
         ]).into_dyn();
 
-        assert_eq!(wf_array, wf.array);
+        assert_eq!(wf_array, wf.array());
         assert_eq!(transformed_array, wf_array_transformed)
     }
 
@@ -124,4 +146,117 @@ mod fft_tests {
         assert_eq!(norm1, norm3);
         // assert_eq!(norm1, norm2);
     }
+
+    #[test]
+    fn test_fft_layouts() {
+        let shape = (6, 8, 20);
+        let array = Array3::from_shape_fn(shape, |(i, j, k)| {
+            Complex64::new((i * j) as f64 + 0.1 * k as f64, (j as f64 - k as f64).sin())
+        })
+        .into_dyn();
+        let grids = vec![
+            Grid::new_linear_countable("x", 0.0, 1.0, shape.0, 0),
+            Grid::new_linear_countable("y", 0.0, 1.0, shape.1, 1),
+            Grid::new_linear_countable("z", 0.0, 1.0, shape.2, 2),
+        ];
+
+        for axis in 0..3 {
+            let expected = naive_fft(&array, axis);
+
+            let mut wf = WaveFunction::new(array.clone(), grids.clone());
+            let mut fft = FFTTransformation::new(&grids[axis], "k");
+            fft.transform(&mut wf);
+            for (x, y) in wf.array().iter().zip(expected.iter()) {
+                assert!((x - y).norm() < 1e-12);
+            }
+            fft.inverse_transform(&mut wf);
+            for (x, y) in wf.array().iter().zip(array.iter()) {
+                assert!((x - y).norm() < 1e-12);
+            }
+
+            // Fortran memory layout
+            let mut fortran = ArrayD::zeros(IxDyn(array.shape()).f());
+            fortran.assign(&array);
+            let mut wf = WaveFunction::new(fortran, grids.clone());
+            assert!(!wf.array().is_standard_layout());
+            fft.transform(&mut wf);
+            for (x, y) in wf.array().iter().zip(expected.iter()) {
+                assert!((x - y).norm() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_folded_normalization() {
+        let grid = Grid::new_linear_continuos("x", -5.0, 5.0, 64, 0);
+        let array: ArrayD<Complex64> = grid.nodes.iter()
+            .map(|x| Complex64::from((-x * x).exp()))
+            .collect::<Array1<Complex64>>()
+            .into_dyn();
+        let operator: Array1<Complex64> = (0..64).map(|i| Complex64::new(0.0, 0.1 * i as f64).exp()).collect();
+
+        let mut normalized = WaveFunction::new(array.clone(), vec![grid.clone()]);
+        let mut fft = FFTTransformation::new(&grid, "k");
+        let mut propagator = OneDimPropagator::new(64, 0);
+        propagator.set_operator(operator.clone());
+        fft.transform(&mut normalized);
+        propagator.apply(&mut normalized, 0.0);
+        fft.inverse_transform(&mut normalized);
+
+        let mut folded = WaveFunction::new(array, vec![grid.clone()]);
+        let mut fft = FFTTransformation::new(&grid, "k");
+        let mut propagator = OneDimPropagator::new(64, 0);
+        propagator.set_operator(operator);
+        fft.fold_normalization_into(&mut propagator);
+        fft.transform(&mut folded);
+        propagator.apply(&mut folded, 0.0);
+        fft.inverse_transform(&mut folded);
+
+        for (x, y) in folded.array().iter().zip(normalized.array().iter()) {
+            assert!((x - y).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_multi_axis_fft() {
+        let shape = (6, 8, 20);
+        let array = Array3::from_shape_fn(shape, |(i, j, k)| {
+            Complex64::new((i * j) as f64 + 0.1 * k as f64, (j as f64 - k as f64).sin())
+        })
+        .into_dyn();
+        let grids = vec![
+            Grid::new_linear_countable("x", 0.0, 1.0, shape.0, 0),
+            Grid::new_linear_countable("y", 0.0, 2.0, shape.1, 1),
+            Grid::new_linear_countable("z", 0.0, 3.0, shape.2, 2),
+        ];
+
+        let mut expected = WaveFunction::new(array.clone(), grids.clone());
+        FFTTransformation::new(&grids[0], "k_x").transform(&mut expected);
+        FFTTransformation::new(&grids[2], "k_z").transform(&mut expected);
+
+        let mut wf = WaveFunction::new(array.clone(), grids.clone());
+        let mut fft = MultiFFTTransformation::new(&[&grids[0], &grids[2]], &["k_x", "k_z"]);
+        fft.transform(&mut wf);
+
+        assert_eq!(wf.grids()[0].name, "k_x");
+        assert_eq!(wf.grids()[1].name, "y");
+        assert_eq!(wf.grids()[2].name, "k_z");
+        for (x, y) in wf.array().iter().zip(expected.array().iter()) {
+            assert!((x - y).norm() < 1e-12);
+        }
+
+        fft.inverse_transform(&mut wf);
+        assert_eq!(wf.grids()[0].name, "x");
+        for (x, y) in wf.array().iter().zip(array.iter()) {
+            assert!((x - y).norm() < 1e-12);
+        }
+
+        let kinetic = n_dim_kinetic_hamiltonian(&wf, &[&grids[0], &grids[2]], &[1.0, 2.0]);
+        let k_x = &expected.grids()[0].nodes;
+        let k_z = &expected.grids()[2].nodes;
+        for ((i, _, k), value) in kinetic.into_dimensionality::<Ix3>().unwrap().indexed_iter() {
+            let energy = k_x[i] * k_x[i] / 2.0 + k_z[k] * k_z[k] / 4.0;
+            assert!((value - energy).abs() < 1e-12 * energy.max(1.0));
+        }
+    }
 }
@@ -0,0 +1,233 @@
+#[cfg(test)]
+mod hamiltonian_tests {
+    use ndarray::{Array1, Array2, ArrayD, IxDyn};
+    use num::complex::Complex64;
+    use quantum::{
+        particle_factory::create_atom,
+        particles::Particles,
+        units::energy_units::{Energy, Kelvin},
+    };
+    use split_operator::{
+        grid::Grid,
+        hamiltonian::{Hamiltonian, HamiltonianTerm, Splitting},
+        hamiltonian_factory::{analytic_potentials::harmonic, kinetic_operator::kinetic_hamiltonian},
+        propagation::{OperationStack, Propagation},
+        propagator::{
+            fft_transformation::FFTTransformation,
+            propagator_factory::{n_dim_into_propagator, one_dim_into_propagator},
+            transformation::Order,
+        },
+        time_grid::{TimeGrid, TimeStep},
+        wave_function::WaveFunction,
+    };
+
+    const OMEGA: f64 = 0.001;
+
+    fn harmonic_hamiltonian(grid: &Grid, collision_params: &Particles) -> Hamiltonian {
+        let potential: Array1<f64> = grid.nodes
+            .iter()
+            .map(|&x| harmonic(x, 0.0, collision_params.red_mass(), OMEGA))
+            .collect();
+
+        let mut hamiltonian = Hamiltonian::new();
+        hamiltonian.add_term(HamiltonianTerm::one_dim("potential", potential, grid));
+        hamiltonian.add_term(
+            HamiltonianTerm::one_dim("kinetic", kinetic_hamiltonian(grid, collision_params), grid)
+                .in_basis(FFTTransformation::new(grid, "momentum")),
+        );
+
+        hamiltonian
+    }
+
+    /// Returns superposition of harmonic oscillator ground and first excited states with given amplitudes.
+    fn harmonic_state(grid: &Grid, mass: f64, ground: f64, excited: f64) -> WaveFunction {
+        let alpha = mass * OMEGA;
+        let array = ArrayD::from_shape_fn(IxDyn(&[grid.nodes_no]), |i| {
+            let x = grid.nodes[i[0]];
+            let gaussian = (-alpha * x * x / 2.0).exp();
+
+            Complex64::from(ground * gaussian + excited * (2.0 * alpha).sqrt() * x * gaussian)
+        });
+        let mut wave_function = WaveFunction::new(array, vec![grid.clone()]);
+        wave_function.normalize(1.0);
+
+        wave_function
+    }
+
+    #[test]
+    fn test_harmonic_energies() {
+        let grid = Grid::new_linear_continuos("space", -4.0, 4.0, 256, 0);
+        let collision_params = Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        );
+        let mass = collision_params.red_mass();
+        let mut hamiltonian = harmonic_hamiltonian(&grid, &collision_params);
+
+        let ground = harmonic_state(&grid, mass, 1.0, 0.0);
+        let statistics = hamiltonian.energy_statistics(&ground);
+        assert!((statistics.mean / OMEGA - 0.5).abs() < 1e-8);
+        assert!(statistics.variance.abs() / OMEGA.powi(2) < 1e-8);
+        assert!(statistics.residual / OMEGA < 1e-6);
+
+        let excited = harmonic_state(&grid, mass, 0.0, 1.0);
+        let statistics = hamiltonian.energy_statistics(&excited);
+        assert!((statistics.mean / OMEGA - 1.5).abs() < 1e-8);
+        assert!(statistics.residual / OMEGA < 1e-6);
+
+        let superposition = harmonic_state(&grid, mass, 1.0, 1.0);
+        let statistics = hamiltonian.energy_statistics(&superposition);
+        assert!((statistics.mean / OMEGA - 1.0).abs() < 1e-8);
+        assert!((statistics.variance / OMEGA.powi(2) - 0.25).abs() < 1e-8);
+        assert!((statistics.residual / OMEGA - 0.5).abs() < 1e-6);
+
+        // energy evaluation has no side effects on the propagation
+        let mut propagation = Propagation::default();
+        propagation.set_wave_function(superposition.clone());
+        let statistics_propagation = propagation.energy_statistics(&mut hamiltonian);
+        assert!((statistics_propagation.mean - statistics.mean).abs() < 1e-15);
+        assert!(propagation.wave_function().array() == superposition.array());
+        assert!(propagation.wave_function().grids()[0].name == "space");
+    }
+
+    fn collision_params() -> Particles {
+        Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        )
+    }
+
+    /// Two dimensional harmonic trap with displaced initial gaussian.
+    fn harmonic_2d() -> (Vec<Grid>, WaveFunction, ArrayD<f64>) {
+        let grids = vec![
+            Grid::new_linear_continuos("x", -4.0, 4.0, 64, 0),
+            Grid::new_linear_continuos("y", -4.0, 4.0, 48, 1),
+        ];
+        let mass = collision_params().red_mass();
+
+        let array = ArrayD::from_shape_fn(IxDyn(&[64, 48]), |i| {
+            let x = grids[0].nodes[i[0]];
+            let y = grids[1].nodes[i[1]];
+
+            Complex64::from((-(x - 0.5).powi(2) - 2.0 * (y + 0.3).powi(2)).exp())
+        });
+        let mut wave_function = WaveFunction::new(array, grids.clone());
+        wave_function.normalize(1.0);
+
+        let potential = ArrayD::from_shape_fn(IxDyn(&[64, 48]), |i| {
+            harmonic(grids[0].nodes[i[0]], 0.0, mass, OMEGA) + harmonic(grids[1].nodes[i[1]], 0.0, mass, OMEGA)
+        });
+
+        (grids, wave_function, potential)
+    }
+
+    #[test]
+    fn test_generated_operation_stack() {
+        let (grids, wave_function, potential) = harmonic_2d();
+        let collision_params = collision_params();
+        let time_grid = TimeGrid { step: 20.0, step_no: 20, im_time: false };
+
+        let mut hamiltonian = Hamiltonian::new();
+        hamiltonian.add_term(HamiltonianTerm::n_dim("potential", potential.clone()));
+        hamiltonian.add_term(HamiltonianTerm::kinetic(&grids[0], &collision_params));
+        hamiltonian.add_term(HamiltonianTerm::kinetic(&grids[1], &collision_params));
+
+        let splitting = Splitting::Strang { center: "kinetic y".to_string() };
+        let operation_stack = hamiltonian.operation_stack(&wave_function, &time_grid, &splitting);
+        let mut generated = Propagation::new(wave_function.clone(), time_grid.clone(), operation_stack);
+        generated.propagate();
+
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_propagator(Box::new(n_dim_into_propagator(potential, &time_grid, TimeStep::Half)));
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grids[0], "x momentum")), Order::Normal);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(
+            kinetic_hamiltonian(&grids[0], &collision_params),
+            &grids[0],
+            &time_grid,
+            TimeStep::Half,
+        )));
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grids[0], "x momentum")), Order::InverseFirst);
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grids[1], "y momentum")), Order::Normal);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(
+            kinetic_hamiltonian(&grids[1], &collision_params),
+            &grids[1],
+            &time_grid,
+            TimeStep::Full,
+        )));
+        let mut manual = Propagation::new(wave_function.clone(), time_grid.clone(), operation_stack);
+        manual.propagate();
+
+        for (x, y) in generated.wave_function().array().iter().zip(manual.wave_function().array().iter()) {
+            assert!((x - y).norm() < 1e-10);
+        }
+        let grid_names: Vec<&str> = generated.wave_function().grids().iter().map(|grid| grid.name.as_str()).collect();
+        assert_eq!(grid_names, ["x", "y"]);
+        let statistics = generated.energy_statistics(&mut hamiltonian);
+        let initial_statistics = hamiltonian.energy_statistics(&wave_function);
+        assert!((statistics.mean - initial_statistics.mean).abs() / initial_statistics.mean < 1e-3);
+    }
+
+    #[test]
+    fn test_generated_ground_state() {
+        let (grids, wave_function, potential) = harmonic_2d();
+        let collision_params = collision_params();
+        let time_grid = TimeGrid { step: 20.0, step_no: 500, im_time: true };
+
+        let mut hamiltonian = Hamiltonian::new();
+        hamiltonian.add_term(HamiltonianTerm::kinetic(&grids[0], &collision_params));
+        hamiltonian.add_term(HamiltonianTerm::kinetic(&grids[1], &collision_params));
+        hamiltonian.add_term(HamiltonianTerm::n_dim("potential", potential));
+
+        let splitting = Splitting::Strang { center: "potential".to_string() };
+        let operation_stack = hamiltonian.operation_stack(&wave_function, &time_grid, &splitting);
+        assert_eq!(operation_stack.operations_len(), 7);
+
+        let mut propagation = Propagation::new(wave_function, time_grid, operation_stack);
+        propagation.propagate();
+
+        let statistics = propagation.energy_statistics(&mut hamiltonian);
+        assert!((statistics.mean / OMEGA - 1.0).abs() < 1e-4);
+        assert!(statistics.residual / OMEGA < 1e-2);
+    }
+
+    #[test]
+    fn test_channel_matrix_terms() {
+        let grids = vec![
+            Grid::new_linear_continuos("r", 1.0, 5.0, 16, 0),
+            Grid::new_linear_countable("channel", 0.0, 1.0, 2, 1),
+        ];
+        let array = ArrayD::from_shape_fn(IxDyn(&[16, 2]), |i| Complex64::from((-(grids[0].nodes[i[0]] - 3.0).powi(2)).exp()));
+        let wave_function = WaveFunction::new(array, grids.clone());
+        let time_grid = TimeGrid { step: 0.5, step_no: 4, im_time: false };
+
+        let mut hamiltonian = Hamiltonian::new();
+        hamiltonian.add_term(HamiltonianTerm::channel_matrix(
+            "coupling",
+            |r| ndarray::array![[Complex64::from(r[0]), Complex64::from(0.1)], [Complex64::from(0.1), Complex64::from(-r[0])]],
+            &[&grids[0]],
+            1,
+            true,
+        ));
+        hamiltonian.add_term(HamiltonianTerm::channel_matrix(
+            "absorbing",
+            |r| Array2::from_diag(&ndarray::arr1(&[Complex64::new(0.0, -0.01 * r[0]), Complex64::from(0.0)])),
+            &[&grids[0]],
+            1,
+            false,
+        ));
+
+        let splitting = Splitting::Strang { center: "absorbing".to_string() };
+        let operation_stack = hamiltonian.operation_stack(&wave_function, &time_grid, &splitting);
+        let mut propagation = Propagation::new(wave_function, time_grid, operation_stack);
+        let report = propagation.propagate();
+
+        let generators: Vec<Option<String>> = propagation.manifest("channels", &report)
+            .operations
+            .into_iter()
+            .map(|operation| operation.description.generator)
+            .collect();
+        assert_eq!(generators, [Some("HermitianMatrix".to_string()), Some("Matrix".to_string())]);
+    }
+}
@@ -0,0 +1,161 @@
+mod common;
+
+#[cfg(test)]
+mod manifest_tests {
+    use ndarray::Array1;
+    use num::complex::Complex64;
+    use quantum::{
+        particle_factory::create_atom,
+        particles::Particles,
+        units::energy_units::{Energy, Kelvin},
+    };
+    use split_operator::{
+        control::Apply,
+        hamiltonian_factory::{
+            absorbing_potentials::{AbsorbingForm, AbsorbingPotential, Boundary},
+            analytic_potentials::harmonic,
+            kinetic_operator::kinetic_hamiltonian,
+        },
+        manifest::{array_hash, OperationDescription, RunManifest},
+        propagation::{OperationStack, Propagation},
+        propagator::{
+            fft_transformation::FFTTransformation,
+            propagator_factory::{absorbing_into_propagator, one_dim_into_propagator},
+            transformation::Order,
+        },
+        report::OperationKind,
+        time_grid::{TimeGrid, TimeStep},
+        wave_function_saver::StateSaver,
+    };
+
+    use crate::common::{harmonic_grid, wave_packet};
+
+    /// Propagation of the Li6 - Li7 wave packet in the harmonic trap saving 10 frames of the density as "position".
+    fn saved_propagation() -> Propagation {
+        let time_grid = TimeGrid { step: 50.0, step_no: 100, im_time: false };
+        let grid = harmonic_grid();
+        let collision_params = Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        );
+        let potential: Array1<f64> = grid.nodes
+            .iter()
+            .map(|&x| harmonic(x, 0.0, collision_params.red_mass(), 0.001))
+            .collect();
+        let kinetic = kinetic_hamiltonian(&grid, &collision_params);
+
+        let mut operation_stack = OperationStack::new();
+        let saver = StateSaver::new("position".to_string(), &time_grid, &grid, 10);
+        operation_stack.add_saver(Box::new(saver), Apply::SecondHalf);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(potential, &grid, &time_grid, TimeStep::Half)));
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grid, "momentum")), Order::Normal);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(kinetic, &grid, &time_grid, TimeStep::Full)));
+
+        Propagation::new(wave_packet(2.0), time_grid, operation_stack)
+    }
+
+    #[test]
+    fn test_manifest() {
+        let mut propagation = saved_propagation();
+        let report = propagation.propagate();
+        let mut manifest = propagation.manifest("harmonic", &report);
+        manifest.insert_metadata("frequency", 0.001);
+
+        assert_eq!(manifest.grids.len(), 1);
+        assert_eq!(manifest.grids[0].name, "x");
+        assert_eq!(manifest.time_schedule[0].step_no, 100);
+        assert!((manifest.time - 5000.0).abs() < 1e-8);
+        assert_eq!(manifest.operations.len(), 4);
+        assert_eq!(manifest.operations[0].kind, OperationKind::Saver);
+        assert_eq!(manifest.operations[0].apply.as_deref(), Some("SecondHalf"));
+        assert!(manifest.operations.iter().any(|op| op.order.is_some()));
+        assert_eq!(manifest.outputs, vec!["position", "position_x_grid", "position_time"]);
+        let names: Vec<String> = propagation.saver_outputs().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(manifest.outputs, names);
+
+        assert_eq!(manifest.operations[0].description, OperationDescription::default());
+        let potential = &manifest.operations[1].description;
+        assert_eq!(potential.step.as_deref(), Some("Half"));
+        assert_eq!(potential.dimensions, vec![0]);
+        assert_eq!(potential.generator.as_deref(), Some("Hamiltonian"));
+        let transformation = &manifest.operations[2].description;
+        assert_eq!(transformation.dimensions, vec![0]);
+        assert_eq!(transformation.grids, vec!["momentum"]);
+        assert_eq!(manifest.operations[3].description.step.as_deref(), Some("Full"));
+
+        let loaded = RunManifest::from_json(&manifest.to_json()).unwrap();
+        assert_eq!(loaded.report.step_no, 100);
+        assert_eq!(loaded.grids[0].nodes, manifest.grids[0].nodes);
+        assert_eq!(loaded.operations.len(), manifest.operations.len());
+        assert_eq!(loaded.operations[1].description, manifest.operations[1].description);
+        assert_eq!(loaded.metadata["frequency"], 0.001);
+    }
+
+    #[test]
+    fn test_load() {
+        let mut propagation = saved_propagation();
+        let report = propagation.propagate();
+        let manifest = propagation.manifest("harmonic_load", &report);
+
+        let directory = std::env::temp_dir().join("split_operator_manifest_tests");
+        std::fs::create_dir_all(&directory).unwrap();
+        let directory = directory.to_str().unwrap();
+        std::fs::write(format!("{directory}/harmonic_load_manifest.json"), manifest.to_json()).unwrap();
+
+        let loaded = RunManifest::load(directory, "harmonic_load").unwrap();
+        assert_eq!(loaded.name, "harmonic_load");
+        assert_eq!(loaded.outputs, manifest.outputs);
+        assert!(loaded.read_output(directory, "missing").is_err());
+        assert!(RunManifest::load(directory, "missing").is_err());
+    }
+
+    #[test]
+    fn test_operation_parameters() {
+        let time_grid = TimeGrid { step: 50.0, step_no: 10, im_time: false };
+        let grid = harmonic_grid();
+        let collision_params = Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        );
+        let mass = collision_params.red_mass();
+        let mut absorbing = AbsorbingPotential::new(AbsorbingForm::Polynomial { order: 2, width: 1.0 }, Boundary::Both, 1e-6, mass);
+        absorbing.set_transmission(1e-4);
+        let potential = absorbing.potential(&grid);
+
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_propagator(Box::new(absorbing_into_propagator(potential.clone(), &grid, &time_grid, TimeStep::Half)));
+        operation_stack.name_last_operation("absorber");
+        operation_stack.describe_last_operation(&absorbing);
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grid, "momentum")), Order::Normal);
+        let kinetic = kinetic_hamiltonian(&grid, &collision_params);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(kinetic, &grid, &time_grid, TimeStep::Full)));
+        operation_stack.name_last_operation("kinetic");
+        operation_stack.describe_last_operation(serde_json::json!({ "mass": mass }));
+
+        let mut propagation = Propagation::new(wave_packet(2.0), time_grid, operation_stack);
+        let report = propagation.propagate();
+        let manifest = propagation.manifest("absorbed_parameters", &report);
+
+        let absorber = &manifest.operation("absorber").unwrap().description;
+        assert_eq!(absorber.generator.as_deref(), Some("Absorbing"));
+        assert_eq!(absorber.parameters["mass"], mass);
+        assert_eq!(absorber.hamiltonian_hash, Some(array_hash([&potential.mapv(Complex64::from)])));
+        assert_eq!(manifest.operation("kinetic").unwrap().description.parameters["mass"], mass);
+        assert!(manifest.operations[1].description.parameters.is_empty());
+
+        let directory = std::env::temp_dir().join("split_operator_manifest_tests");
+        std::fs::create_dir_all(&directory).unwrap();
+        let directory = directory.to_str().unwrap();
+        std::fs::write(format!("{directory}/absorbed_parameters_manifest.json"), manifest.to_json()).unwrap();
+
+        let loaded = RunManifest::load(directory, "absorbed_parameters").unwrap();
+        assert_eq!(loaded.operations[0].description, manifest.operations[0].description);
+        let rebuilt: AbsorbingPotential = loaded.operation_parameters("absorber").unwrap();
+        assert_eq!(rebuilt, absorbing);
+        assert_eq!(rebuilt.potential(&grid), potential);
+        assert!(loaded.operation_parameters::<AbsorbingPotential>("kinetic").is_err());
+        assert!(loaded.operation_parameters::<AbsorbingPotential>("missing").is_err());
+    }
+}
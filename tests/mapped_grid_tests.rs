@@ -0,0 +1,215 @@
+#[cfg(test)]
+mod mapped_grid_tests {
+    use ndarray::{Array1, Array2, ArrayD, IxDyn};
+    use num::complex::Complex64;
+    use quantum::{
+        particle::Particle,
+        particles::Particles,
+        units::{
+            energy_units::{Energy, Kelvin},
+            mass_units::{Dalton, Mass},
+        },
+    };
+    use split_operator::{
+        grid::Grid,
+        hamiltonian::{Hamiltonian, HamiltonianTerm, Splitting},
+        hamiltonian_factory::kinetic_operator::kinetic_hamiltonian,
+        linear_algebra::symmetric_eigen,
+        propagation::Propagation,
+        propagator::{
+            fft_transformation::FFTTransformation, mapped_kinetic_propagator::MappedKineticPropagator,
+            mapped_transformation::MappedTransformation, propagator_factory::one_dim_into_propagator,
+            transformation::Transformation, Propagator,
+        },
+        time_grid::{TimeGrid, TimeStep},
+        wave_function::{gaussian_distribution, WaveFunction},
+    };
+
+    fn collision_params() -> Particles {
+        Particles::new_pair(
+            Particle::new("a", Mass(1.0, Dalton)),
+            Particle::new("b", Mass(1.0, Dalton)),
+            Energy(1e-7, Kelvin),
+        )
+    }
+
+    fn wave_packet(grid: &Grid, center: f64) -> WaveFunction {
+        let array = ArrayD::from_shape_fn(IxDyn(&[grid.nodes_no]), |i| gaussian_distribution(grid.nodes[i[0]], center, 0.5, 2.0));
+
+        WaveFunction::new(array, vec![grid.clone()])
+    }
+
+    /// Returns the kinetic energy operator acting on sqrt(J) psi as a dense matrix.
+    fn kinetic_matrix(propagator: &MappedKineticPropagator, grid: &Grid) -> Array2<f64> {
+        let nodes_no = grid.nodes_no;
+        let mapped = MappedTransformation::new(grid, "x").grid_transformation;
+        let mut matrix = Array2::zeros((nodes_no, nodes_no));
+
+        for j in 0..nodes_no {
+            let mut array = ArrayD::zeros(IxDyn(&[nodes_no]));
+            array[j] = Complex64::from(1.0);
+            let mut wave_function = WaveFunction::new(array, vec![mapped.clone()]);
+
+            propagator.kinetic_action(&mut wave_function);
+            for i in 0..nodes_no {
+                let value = wave_function.array()[i];
+                assert!(value.im.abs() < 1e-12);
+                matrix[[i, j]] = value.re;
+            }
+        }
+
+        matrix
+    }
+
+    #[test]
+    fn uniform_mapping() {
+        let collision_params = collision_params();
+        let grid = Grid::new_linear_countable("r", 0.0, 10.0, 64, 0);
+        let mapped = Grid::new_mapped("r", 0.0, 10.0, 64, 0, |_| 1.0);
+
+        for (x, y) in grid.nodes.iter().zip(mapped.nodes.iter()) {
+            assert!((x - y).abs() < 1e-10);
+        }
+
+        for im_time in [false, true] {
+            let time_grid = TimeGrid { step: 100.0, step_no: 1, im_time };
+
+            let mut fft = FFTTransformation::new(&grid, "momentum");
+            let mut kinetic = one_dim_into_propagator(kinetic_hamiltonian(&grid, &collision_params), &grid, &time_grid, TimeStep::Half);
+            let mut expected = wave_packet(&grid, 5.0);
+            fft.transform(&mut expected);
+            kinetic.apply(&mut expected, 0.0);
+            fft.inverse_transform(&mut expected);
+
+            let mut transformation = MappedTransformation::new(&mapped, "x");
+            let mut propagator = MappedKineticPropagator::new(&mapped, &collision_params, &time_grid, TimeStep::Half);
+            let mut wave_function = wave_packet(&mapped, 5.0);
+            transformation.transform(&mut wave_function);
+            propagator.apply(&mut wave_function, 0.0);
+            transformation.inverse_transform(&mut wave_function);
+            assert_eq!(wave_function.grids()[0].name, "r");
+
+            let scale = expected.array().iter().fold(0.0f64, |acc, x| acc.max(x.norm()));
+            for (x, y) in expected.array().iter().zip(wave_function.array().iter()) {
+                assert!((x - y).norm() < 1e-10 * scale, "{x} {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn morse_bound_states() {
+        let collision_params = collision_params();
+        let mass = collision_params.red_mass();
+        let (depth, a, r_e) = (0.01, 1.0, 3.0);
+        let potential = |r: f64| depth * ((-2.0 * a * (r - r_e)).exp() - 2.0 * (-a * (r - r_e)).exp());
+
+        let grid = Grid::new_fattal_kosloff("r", 1.5, 50.0, 256, 0, potential, mass, depth);
+        let near_spacing = grid.nodes.windows(2).map(|x| x[1] - x[0]).fold(f64::MAX, f64::min);
+        let far_spacing = grid.nodes[255] - grid.nodes[254];
+        assert!(far_spacing > 2.0 * near_spacing);
+
+        let time_grid = TimeGrid { step: 1.0, step_no: 1, im_time: false };
+        let propagator = MappedKineticPropagator::new(&grid, &collision_params, &time_grid, TimeStep::Full);
+
+        let mut hamiltonian = kinetic_matrix(&propagator, &grid);
+        for ((i, j), value) in hamiltonian.indexed_iter() {
+            assert!((value - hamiltonian[[j, i]]).abs() < 1e-12 * propagator.max_energy());
+        }
+        for (i, r) in grid.nodes.iter().enumerate() {
+            hamiltonian[[i, i]] += potential(*r);
+        }
+        let (bound_energies, _) = symmetric_eigen(&hamiltonian);
+
+        let omega = a * (2.0 * depth / mass).sqrt();
+        let expected: Array1<f64> = (0..3)
+            .map(|n| {
+                let vibrational = omega * (n as f64 + 0.5);
+                -depth + vibrational - vibrational * vibrational / (4.0 * depth)
+            })
+            .collect();
+
+        for (x, y) in expected.iter().zip(bound_energies.iter()) {
+            assert!((x - y).abs() < 1e-7, "{x} {y}");
+        }
+    }
+
+    #[test]
+    fn chebyshev_propagation() {
+        let collision_params = collision_params();
+        let mass = collision_params.red_mass();
+        let potential = |r: f64| 0.01 * ((-2.0 * (r - 3.0)).exp() - 2.0 * (-(r - 3.0)).exp());
+        let grid = Grid::new_fattal_kosloff("r", 1.5, 30.0, 128, 0, potential, mass, 0.01);
+
+        let time_grid = TimeGrid { step: 1.0, step_no: 1, im_time: false };
+        let mut propagator = MappedKineticPropagator::new(&grid, &collision_params, &time_grid, TimeStep::Full);
+        let (energies, eigenvectors) = symmetric_eigen(&kinetic_matrix(&propagator, &grid));
+        assert!(energies.iter().all(|&energy| energy > -1e-10 && energy <= propagator.max_energy()));
+
+        for time_grid in [
+            TimeGrid { step: 200.0, step_no: 1, im_time: false },
+            TimeGrid { step: 200.0, step_no: 1, im_time: true },
+            TimeGrid { step: -50.0, step_no: 1, im_time: false },
+        ] {
+            propagator.update_time_grid(&TimeGrid::default(), &time_grid);
+
+            let mut wave_function = wave_packet(&grid, 5.0);
+            MappedTransformation::new(&grid, "x").transform(&mut wave_function);
+            let phi: Array1<Complex64> = wave_function.array().iter().copied().collect();
+            propagator.apply(&mut wave_function, 0.0);
+
+            let exponent = energies.mapv(|energy| {
+                if time_grid.im_time {
+                    Complex64::from((-energy * time_grid.step).exp())
+                } else {
+                    Complex64::new(0.0, -energy * time_grid.step).exp()
+                }
+            });
+            let eigenvectors = eigenvectors.mapv(Complex64::from);
+            let expected = eigenvectors.dot(&(exponent * eigenvectors.t().dot(&phi)));
+
+            let scale = expected.iter().fold(0.0f64, |acc, x| acc.max(x.norm()));
+            for (x, y) in expected.iter().zip(wave_function.array().iter()) {
+                assert!((x - y).norm() < 1e-10 * scale, "{x} {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn mapped_hamiltonian_relaxation() {
+        let collision_params = collision_params();
+        let mass = collision_params.red_mass();
+        let (depth, a, r_e) = (0.01, 1.0, 3.0);
+        let potential = |r: f64| depth * ((-2.0 * a * (r - r_e)).exp() - 2.0 * (-a * (r - r_e)).exp());
+        let grid = Grid::new_fattal_kosloff("r", 1.5, 30.0, 128, 0, potential, mass, depth);
+
+        let mut hamiltonian = Hamiltonian::new();
+        hamiltonian.add_term(HamiltonianTerm::one_dim("potential", grid.nodes.iter().map(|&r| potential(r)).collect(), &grid));
+        hamiltonian.add_term(HamiltonianTerm::mapped_kinetic(&grid, &collision_params));
+
+        let mut wave_function = wave_packet(&grid, r_e);
+        wave_function.normalize(1.0);
+        let time_grid = TimeGrid { step: 20.0, step_no: 300, im_time: true };
+        let splitting = Splitting::Strang { center: "potential".to_string() };
+        let operation_stack = hamiltonian.operation_stack(&wave_function, &time_grid, &splitting);
+
+        let mut propagation = Propagation::new(wave_function, time_grid, operation_stack);
+        propagation.propagate();
+        assert_eq!(propagation.wave_function().grids()[0].name, "r");
+
+        let omega = a * (2.0 * depth / mass).sqrt();
+        let expected = -depth + omega / 2.0 - omega * omega / (16.0 * depth);
+        let statistics = propagation.energy_statistics(&mut hamiltonian);
+        assert!((statistics.mean - expected).abs() < 1e-6, "{} {expected}", statistics.mean);
+        assert!(statistics.variance.abs() < 1e-8);
+    }
+
+    #[test]
+    #[should_panic(expected = "Kinetic hamiltonian applied by FFT requires uniform grid r")]
+    fn uniform_kinetic_on_mapped_grid() {
+        let collision_params = collision_params();
+        let potential = |r: f64| 0.01 * ((-2.0 * (r - 3.0)).exp() - 2.0 * (-(r - 3.0)).exp());
+        let grid = Grid::new_fattal_kosloff("r", 1.5, 30.0, 128, 0, potential, collision_params.red_mass(), 0.01);
+
+        HamiltonianTerm::kinetic(&grid, &collision_params);
+    }
+}
@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod matrix_exponential_tests {
+    use ndarray::{array, Array2, ArrayD, IxDyn};
+    use num::complex::Complex64;
+    use split_operator::{
+        grid::Grid,
+        linear_algebra::{hermitian_exponential, matrix_exponential},
+        propagator::{
+            propagator_factory::{complex_matrix_into_propagator, hermitian_matrix_into_propagator},
+            Propagator,
+        },
+        time_grid::{TimeGrid, TimeStep},
+        wave_function::WaveFunction,
+    };
+
+    fn coupling_matrix(r: f64) -> Array2<Complex64> {
+        array![
+            [Complex64::from(r), Complex64::new(0.3, 0.1 * r)],
+            [Complex64::new(0.3, -0.1 * r), Complex64::from(-r)],
+        ]
+    }
+
+    fn assert_close(a: &Array2<Complex64>, b: &Array2<Complex64>, tolerance: f64) {
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).norm() < tolerance, "{x} != {y}");
+        }
+    }
+
+    #[test]
+    fn test_exponentials_agree() {
+        for r in [0.1, 1.0, 7.0] {
+            let matrix = coupling_matrix(r);
+            let factor = Complex64::new(-0.2, -1.3);
+
+            let hermitian = hermitian_exponential(&matrix, factor);
+            let pade = matrix_exponential(&(&matrix * factor));
+            assert_close(&hermitian, &pade, 1e-12);
+        }
+
+        let diagonal = array![[Complex64::new(0.5, -2.0), Complex64::from(0.0)], [Complex64::from(0.0), Complex64::new(-3.0, 1.0)]];
+        let exponential = matrix_exponential(&diagonal);
+        let expected = array![
+            [Complex64::new(0.5, -2.0).exp(), Complex64::from(0.0)],
+            [Complex64::from(0.0), Complex64::new(-3.0, 1.0).exp()]
+        ];
+        assert_close(&exponential, &expected, 1e-12);
+    }
+
+    fn test_wave_function(grid: &Grid, channels: &Grid) -> WaveFunction {
+        let array = ArrayD::from_shape_fn(IxDyn(&[grid.nodes_no, channels.nodes_no]), |i| {
+            Complex64::new(1.0 + i[1] as f64, 0.2 * i[0] as f64)
+        });
+
+        WaveFunction::new(array, vec![grid.clone(), channels.clone()])
+    }
+
+    #[test]
+    fn test_matrix_propagators() {
+        let grid = Grid::new_linear_continuos("r", 1.0, 5.0, 8, 0);
+        let channels = Grid::new_linear_countable("channel", 0.0, 1.0, 2, 1);
+
+        for im_time in [false, true] {
+            let time = TimeGrid { step: 0.7, step_no: 1, im_time };
+            let dt = if im_time { -Complex64::i() * 0.7 } else { Complex64::from(0.7) };
+
+            let mut hermitian = hermitian_matrix_into_propagator(|r| coupling_matrix(r[0]), &[&grid], 1, &time, TimeStep::Full);
+            let mut half = hermitian_matrix_into_propagator(|r| coupling_matrix(r[0]), &[&grid], 1, &time, TimeStep::Half);
+            let mut complex = complex_matrix_into_propagator(|r| coupling_matrix(r[0]), &[&grid], 1, &time, TimeStep::Full);
+
+            let mut wave_function = test_wave_function(&grid, &channels);
+            let mut expected: Array2<Complex64> = wave_function.array().clone().into_dimensionality().unwrap();
+            for (i, r) in grid.nodes.iter().enumerate() {
+                let operator = hermitian_exponential(&coupling_matrix(*r), -Complex64::i() * dt);
+                let row = operator.dot(&expected.row(i).to_owned());
+                expected.row_mut(i).assign(&row);
+            }
+
+            hermitian.apply(&mut wave_function, 0.0);
+            assert_close(&wave_function.array().clone().into_dimensionality().unwrap(), &expected, 1e-12);
+
+            let mut wave_function = test_wave_function(&grid, &channels);
+            half.apply(&mut wave_function, 0.0);
+            half.apply(&mut wave_function, 0.0);
+            assert_close(&wave_function.array().clone().into_dimensionality().unwrap(), &expected, 1e-12);
+
+            let mut wave_function = test_wave_function(&grid, &channels);
+            complex.apply(&mut wave_function, 0.0);
+            assert_close(&wave_function.array().clone().into_dimensionality().unwrap(), &expected, 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_absorbing_matrix() {
+        let grid = Grid::new_linear_continuos("r", 0.0, 1.0, 4, 0);
+        let channels = Grid::new_linear_countable("channel", 0.0, 1.0, 2, 1);
+        let time = TimeGrid { step: 2.0, step_no: 1, im_time: false };
+
+        let absorbing = |_: &[f64]| {
+            array![[Complex64::new(0.0, -0.5), Complex64::from(0.0)], [Complex64::from(0.0), Complex64::from(0.0)]]
+        };
+        let mut propagator = complex_matrix_into_propagator(absorbing, &[&grid], 1, &time, TimeStep::Full);
+
+        let mut wave_function = test_wave_function(&grid, &channels);
+        let initial = wave_function.array().clone();
+        propagator.apply(&mut wave_function, 0.0);
+
+        for (x, y) in wave_function.array().iter().zip(initial.iter()).step_by(2) {
+            assert!((x - y * (-1.0f64).exp()).norm() < 1e-12);
+        }
+        for (x, y) in wave_function.array().iter().zip(initial.iter()).skip(1).step_by(2) {
+            assert!((x - y).norm() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod matrix_transformation_tests {
+    use ndarray::{Array2, Array4, ArrayD, Axis, IxDyn, ShapeBuilder};
+    use num::complex::Complex64;
+    use split_operator::{
+        grid::Grid,
+        propagator::{
+            matrix_transformation::MatrixTransformation, state_matrix_transformation::StateMatrixTransformation,
+            transformation::Transformation,
+        },
+        wave_function::WaveFunction,
+    };
+
+    const SHAPE: [usize; 4] = [3, 5, 4, 6];
+
+    fn test_wave_function(fortran: bool) -> WaveFunction {
+        let array = Array4::from_shape_fn(SHAPE, |(i, j, k, l)| {
+            Complex64::new((i + 2 * j) as f64 - 0.5 * k as f64, (l as f64 * 0.3 + j as f64).cos())
+        })
+        .into_dyn();
+        let array = if fortran {
+            let mut fortran_array = ArrayD::zeros(IxDyn(&SHAPE).f());
+            fortran_array.assign(&array);
+            fortran_array
+        } else {
+            array
+        };
+
+        let grids = SHAPE.iter()
+            .enumerate()
+            .map(|(i, &n)| Grid::new_linear_countable(&format!("x{i}"), 0.0, 1.0, n, i))
+            .collect();
+
+        WaveFunction::new(array, grids)
+    }
+
+    fn test_matrix(size: usize, seed: f64) -> Array2<Complex64> {
+        Array2::from_shape_fn((size, size), |(i, j)| Complex64::new((seed * (i + 2 * j) as f64).sin(), seed * (i as f64 - j as f64)))
+    }
+
+    fn naive(array: &ArrayD<Complex64>, matrix: &Array2<Complex64>, axis: usize) -> ArrayD<Complex64> {
+        let mut result = array.clone();
+        for mut lane in result.lanes_mut(Axis(axis)) {
+            let transformed = matrix.dot(&lane);
+            lane.assign(&transformed);
+        }
+
+        result
+    }
+
+    fn assert_close(a: &ArrayD<Complex64>, b: &ArrayD<Complex64>) {
+        assert_eq!(a.shape(), b.shape());
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).norm() < 1e-10, "{x} {y}");
+        }
+    }
+
+    #[test]
+    fn matrix_transformation() {
+        for fortran in [false, true] {
+            for (axis, &size) in SHAPE.iter().enumerate() {
+                let mut wave_function = test_wave_function(fortran);
+                let array = wave_function.array().clone();
+
+                let matrix = test_matrix(size, 0.7);
+                let inverse = test_matrix(size, 0.2);
+                let grid = wave_function.grids()[axis].clone();
+                let mut transformation = MatrixTransformation::new(&grid, Grid::new_linear_countable("t", 0.0, 1.0, size, axis));
+                transformation.set_diagonalization_matrix(matrix.clone(), inverse.clone());
+
+                transformation.transform(&mut wave_function);
+                assert_eq!(wave_function.grids()[axis].name, "t");
+                let transformed = naive(&array, &matrix, axis);
+                assert_close(wave_function.array(), &transformed);
+
+                transformation.inverse_transform(&mut wave_function);
+                assert_eq!(wave_function.grids()[axis].name, grid.name);
+                assert_close(wave_function.array(), &naive(&transformed, &inverse, axis));
+            }
+        }
+    }
+
+    fn state_matrix(axis: usize, dependent: usize) -> StateMatrixTransformation {
+        let grid = Grid::new_linear_countable(&format!("x{axis}"), 0.0, 1.0, SHAPE[axis], axis);
+        let mut transformation = StateMatrixTransformation::new(dependent, &grid, Grid::new_linear_countable("t", 0.0, 1.0, SHAPE[axis], axis));
+
+        let matrices = (0..SHAPE[dependent]).map(|i| test_matrix(SHAPE[axis], 0.1 * i as f64 + 0.3)).collect();
+        let inverses = (0..SHAPE[dependent]).map(|i| test_matrix(SHAPE[axis], 0.2 * i as f64 - 0.1)).collect();
+        transformation.set_diagonalization_matrices(matrices, inverses);
+
+        transformation
+    }
+
+    fn naive_state(array: &mut ArrayD<Complex64>, axis: usize, dependent: usize, seed: impl Fn(usize) -> f64) {
+        for (i, mut block) in array.axis_iter_mut(Axis(dependent)).enumerate() {
+            let block_axis = if dependent < axis { axis - 1 } else { axis };
+            let matrix = test_matrix(SHAPE[axis], seed(i));
+
+            for mut lane in block.lanes_mut(Axis(block_axis)) {
+                let transformed = matrix.dot(&lane);
+                lane.assign(&transformed);
+            }
+        }
+    }
+
+    #[test]
+    fn state_matrix_transformation() {
+        for (axis, dependent) in [(1, 3), (3, 1), (0, 2), (2, 0)] {
+            let mut wave_function = test_wave_function(false);
+            let mut expected = wave_function.array().clone();
+            let mut transformation = state_matrix(axis, dependent);
+
+            transformation.transform(&mut wave_function);
+            naive_state(&mut expected, axis, dependent, |i| 0.1 * i as f64 + 0.3);
+            assert_close(wave_function.array(), &expected);
+
+            transformation.inverse_transform(&mut wave_function);
+            naive_state(&mut expected, axis, dependent, |i| 0.2 * i as f64 - 0.1);
+            assert_close(wave_function.array(), &expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Number of transformation matrices has to match the length of the dependent dimension")]
+    fn state_matrix_count_mismatch() {
+        let mut wave_function = test_wave_function(false);
+        let mut transformation = state_matrix(1, 3);
+        let matrices = vec![test_matrix(SHAPE[1], 0.1); SHAPE[3] - 1];
+        transformation.set_diagonalization_matrices(matrices.clone(), matrices);
+
+        transformation.transform(&mut wave_function);
+    }
+}
@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod non_diagonal_tests {
+    use ndarray::{Array2, Array3, ArrayD, Axis, Dimension, IxDyn, ShapeBuilder};
+    use num::complex::Complex64;
+    use split_operator::{
+        grid::Grid,
+        propagator::{
+            non_diagonal_propagator::NonDiagPropagator, propagator_factory::hermitian_matrix_into_propagator, Propagator,
+        },
+        time_grid::{TimeGrid, TimeStep},
+        wave_function::WaveFunction,
+    };
+
+    const SHAPE: [usize; 3] = [4, 3, 5];
+
+    fn test_wave_function(fortran: bool) -> WaveFunction {
+        let array = Array3::from_shape_fn(SHAPE, |(i, j, k)| {
+            Complex64::new((i + 2 * j) as f64 - 0.5 * k as f64, (k as f64 * 0.3 + j as f64).cos())
+        })
+        .into_dyn();
+        let array = if fortran {
+            let mut fortran_array = ArrayD::zeros(IxDyn(&SHAPE).f());
+            fortran_array.assign(&array);
+            fortran_array
+        } else {
+            array
+        };
+
+        let grids = SHAPE.iter()
+            .enumerate()
+            .map(|(i, &n)| Grid::new_linear_countable(&format!("x{i}"), 0.0, 1.0, n, i))
+            .collect();
+
+        WaveFunction::new(array, grids)
+    }
+
+    fn test_matrix(size: usize, seed: f64) -> Array2<Complex64> {
+        Array2::from_shape_fn((size, size), |(i, j)| Complex64::new((seed * (i + 2 * j) as f64).sin(), seed * (i as f64 - j as f64)))
+    }
+
+    /// Applies matrix given by `matrix_at(index)` along `channel` axis at each index of the array.
+    fn naive_apply(
+        array: &ArrayD<Complex64>,
+        channel: usize,
+        matrix_at: impl Fn(&[usize]) -> Array2<Complex64>,
+    ) -> ArrayD<Complex64> {
+        let mut result = ArrayD::zeros(array.raw_dim());
+        for (index, value) in result.indexed_iter_mut() {
+            let matrix = matrix_at(index.slice());
+            let mut source = index.slice().to_vec();
+            for j in 0..array.shape()[channel] {
+                source[channel] = j;
+                *value += matrix[[index[channel], j]] * array[IxDyn(&source)];
+            }
+        }
+
+        result
+    }
+
+    fn assert_close(a: &ArrayD<Complex64>, b: &ArrayD<Complex64>) {
+        assert_eq!(a.shape(), b.shape());
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).norm() < 1e-10, "{x} != {y}");
+        }
+    }
+
+    #[test]
+    fn test_indexed_operators() {
+        for fortran in [false, true] {
+            for (channel, &size) in SHAPE.iter().enumerate() {
+                let other: Vec<usize> = (0..SHAPE.len()).filter(|&a| a != channel).collect();
+
+                // matrices depending on the last of the other axes only
+                let index_axis = other[1];
+                let operators: Vec<Array2<Complex64>> = (0..2)
+                    .map(|i| test_matrix(size, 0.3 + i as f64))
+                    .collect();
+                let indices = ArrayD::from_shape_fn(IxDyn(&[SHAPE[index_axis]]), |i| i[0] % 2);
+
+                let mut propagator = NonDiagPropagator::new(channel);
+                propagator.set_indexed_operators(operators.clone(), vec![index_axis], indices);
+                assert_eq!(propagator.operators_no(), 2);
+
+                let mut wave_function = test_wave_function(fortran);
+                let expected = naive_apply(wave_function.array(), channel, |index| operators[index[index_axis] % 2].clone());
+                propagator.apply(&mut wave_function, 0.0);
+                assert_close(wave_function.array(), &expected);
+
+                // matrices depending on both other axes given in reversed order
+                let operators: Vec<Array2<Complex64>> = (0..SHAPE[other[0]] * SHAPE[other[1]])
+                    .map(|i| test_matrix(size, 0.1 * i as f64 + 0.2))
+                    .collect();
+                let indices = ArrayD::from_shape_fn(IxDyn(&[SHAPE[other[1]], SHAPE[other[0]]]), |i| {
+                    i[1] * SHAPE[other[1]] + i[0]
+                });
+
+                let mut propagator = NonDiagPropagator::new(channel);
+                propagator.set_indexed_operators(operators.clone(), vec![other[1], other[0]], indices);
+
+                let mut wave_function = test_wave_function(fortran);
+                let expected = naive_apply(wave_function.array(), channel, |index| {
+                    operators[index[other[0]] * SHAPE[other[1]] + index[other[1]]].clone()
+                });
+                propagator.apply(&mut wave_function, 0.0);
+                assert_close(wave_function.array(), &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_repeating_channel_matrices() {
+        let wave_function = test_wave_function(false);
+        let grids = wave_function.grids().to_vec();
+        let time_grid = TimeGrid { step: 0.2, step_no: 1, im_time: false };
+
+        // coupling constant along the last axis is evaluated at each grid point but stored once per node of the first axis
+        let coupling = |x: &[f64]| {
+            Array2::from_shape_fn((SHAPE[1], SHAPE[1]), |(i, j)| Complex64::from((x[0] + 1.0) * (i + j) as f64 + (i * j) as f64))
+        };
+        let mut propagator = hermitian_matrix_into_propagator(coupling, &[&grids[0], &grids[2]], 1, &time_grid, TimeStep::Full);
+        assert_eq!(propagator.operators_no(), SHAPE[0]);
+        let mut expected = hermitian_matrix_into_propagator(coupling, &[&grids[0]], 1, &time_grid, TimeStep::Full);
+
+        let mut indexed = NonDiagPropagator::new(1);
+        let operators = (0..SHAPE[0] * SHAPE[2]).map(|i| test_matrix(SHAPE[1], 0.3 + (i / SHAPE[2]) as f64)).collect();
+        indexed.set_indexed_operators(operators, vec![0, 2], ArrayD::from_shape_fn(IxDyn(&[SHAPE[0], SHAPE[2]]), |i| i[0] * SHAPE[2] + i[1]));
+        assert_eq!(indexed.operators_no(), SHAPE[0]);
+
+        let im_time = TimeGrid { step: 0.3, step_no: 1, im_time: true };
+        propagator.update_time_grid(&time_grid, &im_time);
+        expected.update_time_grid(&time_grid, &im_time);
+        assert_eq!(propagator.operators_no(), SHAPE[0]);
+
+        let mut result = wave_function.clone();
+        let mut expected_result = wave_function;
+        propagator.apply(&mut result, 0.0);
+        expected.apply(&mut expected_result, 0.0);
+        assert_close(result.array(), expected_result.array());
+    }
+
+    #[test]
+    fn test_lane_operators() {
+        let channel = 1;
+        let mut wave_function = test_wave_function(false);
+        let lanes_no = wave_function.array().len() / SHAPE[channel];
+
+        let operators: Vec<Array2<Complex64>> = (0..lanes_no)
+            .map(|i| test_matrix(SHAPE[channel], 0.5 + (i % 3) as f64))
+            .collect();
+
+        let mut propagator = NonDiagPropagator::new(channel);
+        propagator.set_operators(operators.clone());
+        assert_eq!(propagator.operators_no(), 3);
+
+        let mut expected = wave_function.array().clone();
+        for (mut lane, operator) in expected.lanes_mut(Axis(channel)).into_iter().zip(operators.iter()) {
+            let result = operator.dot(&lane);
+            lane.assign(&result);
+        }
+
+        propagator.apply(&mut wave_function, 0.0);
+        assert_close(wave_function.array(), &expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Indices shape has to match the shape of the index axes")]
+    fn test_indices_shape_mismatch() {
+        let mut propagator = NonDiagPropagator::new(1);
+        propagator.set_indexed_operators(
+            vec![test_matrix(SHAPE[1], 0.3)],
+            vec![0],
+            ArrayD::zeros(IxDyn(&[SHAPE[0] + 1])),
+        );
+
+        propagator.apply(&mut test_wave_function(false), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Operator index out of bounds")]
+    fn test_index_out_of_bounds() {
+        let mut propagator = NonDiagPropagator::new(1);
+        propagator.set_indexed_operators(
+            vec![test_matrix(SHAPE[1], 0.3)],
+            vec![0],
+            ArrayD::from_elem(IxDyn(&[SHAPE[0]]), 1),
+        );
+    }
+}
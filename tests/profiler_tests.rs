@@ -0,0 +1,103 @@
+mod common;
+
+#[cfg(test)]
+mod profiler_tests {
+    use ndarray::Array1;
+    use quantum::{
+        particle_factory::create_atom,
+        particles::Particles,
+        units::energy_units::{Energy, Kelvin},
+    };
+    use split_operator::{
+        control::Apply,
+        hamiltonian_factory::{analytic_potentials::harmonic, kinetic_operator::kinetic_hamiltonian},
+        leak_control::LeakControl,
+        propagation::{OperationStack, Propagation},
+        propagator::{fft_transformation::FFTTransformation, propagator_factory::one_dim_into_propagator, transformation::Order},
+        report::OperationKind,
+        saver::Saver,
+        time_grid::{TimeGrid, TimeStep},
+        wave_function::WaveFunction,
+    };
+
+    use crate::common::{harmonic_grid, wave_packet};
+
+    struct NullSaver;
+
+    impl Saver for NullSaver {
+        fn name(&self) -> &str {
+            "NullSaver"
+        }
+
+        fn monitor(&mut self, _wave_function: &mut WaveFunction, _time: f64) {}
+
+        fn save(&self) -> Result<(), &str> {
+            Ok(())
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    fn profiled_propagation(time_grid: &TimeGrid) -> Propagation {
+        let grid = harmonic_grid();
+        let collision_params = Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        );
+        let potential: Array1<f64> = grid.nodes
+            .iter()
+            .map(|&x| harmonic(x, 0.0, collision_params.red_mass(), 0.001))
+            .collect();
+        let kinetic = kinetic_hamiltonian(&grid, &collision_params);
+
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_control(Box::new(LeakControl::new()), Apply::FirstHalf);
+        operation_stack.name_last_operation("leak");
+        operation_stack.add_saver(Box::new(NullSaver), Apply::SecondHalf);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(potential, &grid, time_grid, TimeStep::Half)));
+        operation_stack.name_last_operation("potential");
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grid, "momentum")), Order::Normal);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(kinetic, &grid, time_grid, TimeStep::Full)));
+        operation_stack.name_last_operation("kinetic");
+        operation_stack.enable_profiling();
+
+        Propagation::new(wave_packet(2.0), time_grid.clone(), operation_stack)
+    }
+
+    fn calls(propagation: &Propagation) -> Vec<usize> {
+        propagation.profiles().unwrap().iter().map(|profile| profile.calls).collect()
+    }
+
+    #[test]
+    fn test_profiles() {
+        let time_grid = TimeGrid { step: 25.0, step_no: 10, im_time: false };
+        let mut propagation = profiled_propagation(&time_grid);
+        propagation.propagate();
+
+        let profiles = propagation.profiles().unwrap();
+        let names: Vec<&str> = profiles.iter().map(|profile| profile.name.as_str()).collect();
+        assert_eq!(names, vec!["leak", "NullSaver", "potential", "FFTTransformation", "kinetic"]);
+
+        let type_names: Vec<&str> = profiles.iter().map(|profile| profile.type_name.as_str()).collect();
+        assert_eq!(type_names[0], "LeakControl");
+        assert_eq!(type_names[2], "OneDimPropagator");
+
+        let kinds: Vec<OperationKind> = profiles.iter().map(|profile| profile.kind).collect();
+        assert_eq!(kinds[..2], [OperationKind::Control, OperationKind::Saver]);
+
+        // half-step operations are performed in both halves, center operation once per step,
+        // control and saver only in the half they are applied to
+        assert_eq!(calls(&propagation), vec![10, 10, 20, 20, 10]);
+
+        let table = propagation.profile_table().unwrap();
+        assert!(table.contains("leak") && table.contains("kinetic") && table.contains("NullSaver"));
+
+        // diagnostic propagation is not recorded in the profiles
+        propagation.round_trip_error();
+        assert_eq!(calls(&propagation), vec![10, 10, 20, 20, 10]);
+
+        propagation.reset_profiles();
+        assert!(calls(&propagation).iter().all(|&calls| calls == 0));
+    }
+}
@@ -0,0 +1,97 @@
+mod common;
+
+#[cfg(test)]
+mod propagation_handle_tests {
+    use std::time::{Duration, Instant};
+
+    use ndarray::Array1;
+    use quantum::{
+        particle_factory::create_atom,
+        particles::Particles,
+        units::energy_units::{Energy, Kelvin},
+    };
+    use split_operator::{
+        hamiltonian_factory::{analytic_potentials::harmonic, kinetic_operator::kinetic_hamiltonian},
+        propagation::{OperationStack, Propagation},
+        propagation_handle::PropagationHandle,
+        propagator::{fft_transformation::FFTTransformation, propagator_factory::one_dim_into_propagator, transformation::Order},
+        time_grid::{TimeGrid, TimeStep},
+    };
+
+    use crate::common::{harmonic_grid, wave_packet};
+
+    /// Propagation of the Li6 - Li7 wave packet in the harmonic trap.
+    fn trap_propagation(time_grid: &TimeGrid) -> Propagation {
+        let grid = harmonic_grid();
+        let collision_params = Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        );
+        let potential: Array1<f64> = grid.nodes
+            .iter()
+            .map(|&x| harmonic(x, 0.0, collision_params.red_mass(), 0.001))
+            .collect();
+        let kinetic = kinetic_hamiltonian(&grid, &collision_params);
+
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(potential, &grid, time_grid, TimeStep::Half)));
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grid, "momentum")), Order::Normal);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(kinetic, &grid, time_grid, TimeStep::Full)));
+
+        Propagation::new(wave_packet(2.0), time_grid.clone(), operation_stack)
+    }
+
+    /// Polls the progress of the `handle` until the propagation performs more than `step_no` steps,
+    /// panics if it does not happen within the timeout.
+    fn wait_for_steps(handle: &PropagationHandle, step_no: usize) {
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while handle.progress().step_no <= step_no {
+            assert!(Instant::now() < deadline, "Propagation did not advance past {step_no} steps");
+            std::thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn test_spawn_join() {
+        let handle = trap_propagation(&TimeGrid { step: 50.0, step_no: 300, im_time: false }).spawn();
+        let (mut propagation, report) = handle.join();
+
+        assert_eq!(report.step_no, 300);
+        assert!(report.stop.is_none());
+        assert!((propagation.time() - 15000.0).abs() < 1e-8);
+
+        let report = propagation.propagate();
+        assert_eq!(report.step_no, 300);
+        assert!(report.stop.is_none());
+    }
+
+    #[test]
+    fn test_pause_snapshot_cancel() {
+        let handle = trap_propagation(&TimeGrid { step: 50.0, step_no: usize::MAX, im_time: false }).spawn();
+        wait_for_steps(&handle, 0);
+        handle.pause();
+
+        // snapshot is served at the step boundary where the paused propagation waits
+        let mut snapshot = handle.snapshot().unwrap();
+        let progress = handle.progress();
+        assert!((snapshot.norm() - progress.norm).abs() < 1e-12);
+        assert!((progress.time - 50.0 * progress.step_no as f64).abs() < 1e-6);
+
+        // the paused propagation serves another snapshot without advancing
+        let mut snapshot = handle.snapshot().unwrap();
+        assert_eq!(handle.progress().step_no, progress.step_no);
+        assert!((snapshot.norm() - progress.norm).abs() < 1e-12);
+        assert!(!handle.is_finished());
+
+        handle.resume();
+        wait_for_steps(&handle, progress.step_no);
+
+        handle.cancel();
+        let (propagation, report) = handle.join();
+        let stop = report.stop.unwrap();
+        assert_eq!(stop.condition, "Cancellation");
+        assert_eq!(stop.step_no, report.step_no);
+        assert!((propagation.time() - stop.time).abs() < 1e-6);
+    }
+}
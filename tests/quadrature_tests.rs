@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod quadrature_tests {
+    use std::f64::consts::PI;
+
+    use num::complex::Complex64;
+    use split_operator::{grid::Grid, hamiltonian_factory::legendre_diagonalization::legendre_diagonalization_operator};
+
+    #[test]
+    fn gauss_legendre() {
+        let grid = Grid::new_gauss_legendre("theta", 200, 0);
+        assert!(grid.nodes.windows(2).all(|x| x[0] < x[1]));
+
+        let integral: f64 = grid.weights.iter().sum();
+        assert!((integral - 2.0).abs() < 1e-12);
+
+        let integral: f64 = grid.nodes.iter()
+            .zip(grid.weights.iter())
+            .map(|(theta, w)| w * theta.cos().powi(2))
+            .sum();
+        assert!((integral - 2.0 / 3.0).abs() < 1e-12);
+
+        let [transformation, inverse] = legendre_diagonalization_operator(&grid).get_diagonalization_matrices();
+        let identity = transformation.dot(&inverse);
+        for ((i, j), value) in identity.indexed_iter() {
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!((value - Complex64::from(expected)).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn gauss_hermite() {
+        let grid = Grid::new_gauss_hermite("x", 300, 1.0, 2.0, 0);
+        assert!(grid.nodes.windows(2).all(|x| x[0] < x[1]));
+
+        let integral: f64 = grid.nodes.iter()
+            .zip(grid.weights.iter())
+            .map(|(x, w)| w * (-(x - 1.0) * (x - 1.0) / 4.0).exp() * (x - 1.0).powi(2))
+            .sum();
+        assert!((integral - 4.0 * PI.sqrt()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn gauss_laguerre() {
+        let grid = Grid::new_gauss_laguerre("r", 300, 0.5, 0.5, 0);
+        assert!(grid.nodes.windows(2).all(|x| x[0] < x[1]));
+
+        let integral: f64 = grid.nodes.iter()
+            .zip(grid.weights.iter())
+            .map(|(r, w)| w * (r - 0.5) * (-2.0 * (r - 0.5)).exp())
+            .sum();
+        assert!((integral - 0.25).abs() < 1e-10);
+    }
+}
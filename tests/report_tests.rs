@@ -0,0 +1,197 @@
+mod common;
+
+#[cfg(test)]
+mod report_tests {
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use ndarray::Array1;
+    use quantum::{
+        particle_factory::create_atom,
+        particles::Particles,
+        units::energy_units::{Energy, Kelvin},
+    };
+    use split_operator::{
+        border_dumping::{dumping_both, BorderDumping},
+        control::Apply,
+        hamiltonian_factory::{analytic_potentials::harmonic, kinetic_operator::kinetic_hamiltonian},
+        loss_checker::LossChecker,
+        propagation::{OperationStack, Propagation},
+        propagator::{fft_transformation::FFTTransformation, propagator_factory::one_dim_into_propagator, transformation::Order},
+        report::{ElapsedTimes, OperationKind, PrintReporter, PropagationReport, Reporter},
+        time_grid::{TimeGrid, TimeStep},
+        wave_function::WaveFunction,
+    };
+
+    use crate::common::{harmonic_grid, wave_packet};
+
+    #[derive(Default)]
+    struct Calls {
+        steps: Vec<(usize, f64)>,
+        finished: Vec<usize>,
+    }
+
+    struct RecordingReporter {
+        calls: Arc<Mutex<Calls>>,
+    }
+
+    impl Reporter for RecordingReporter {
+        fn step(&mut self, step: usize, time: f64, _wave_function: &mut WaveFunction) {
+            self.calls.lock().unwrap().steps.push((step, time));
+        }
+
+        fn finish(&mut self, report: &PropagationReport) {
+            self.calls.lock().unwrap().finished.push(report.step_no);
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl SharedBuffer {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(String::from)
+                .collect()
+        }
+    }
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn absorbed_propagation(time_grid: &TimeGrid) -> Propagation {
+        let grid = harmonic_grid();
+        let collision_params = Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        );
+        let potential: Array1<f64> = grid.nodes
+            .iter()
+            .map(|&x| harmonic(x, 0.0, collision_params.red_mass(), 0.001))
+            .collect();
+        let kinetic = kinetic_hamiltonian(&grid, &collision_params);
+
+        let mut dumping = BorderDumping::new(dumping_both(3.0, 0.0, &grid), &grid);
+        dumping.add_loss_checker(LossChecker::new("border"));
+
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_control(Box::new(dumping), Apply::FirstHalf);
+        operation_stack.name_last_operation("absorber");
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(potential, &grid, time_grid, TimeStep::Half)));
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grid, "momentum")), Order::Normal);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(kinetic, &grid, time_grid, TimeStep::Full)));
+
+        Propagation::new(wave_packet(2.0), time_grid.clone(), operation_stack)
+    }
+
+    #[test]
+    fn test_propagation_report() {
+        let time_grid = TimeGrid { step: 25.0, step_no: 40, im_time: false };
+        let mut propagation = absorbed_propagation(&time_grid);
+
+        let report = propagation.propagate();
+        assert_eq!(report.step_no, 40);
+        assert!(report.stop.is_none());
+        assert_eq!(report.operations.len(), 4);
+
+        let absorber = &report.operations[0];
+        assert_eq!(absorber.name, "absorber");
+        assert_eq!(absorber.kind, OperationKind::Control);
+        let loss = absorber.loss.as_ref().unwrap();
+        assert_eq!(loss.name, "border");
+        assert!(loss.loss > 0.0);
+
+        assert!(report.operations[1..].iter().all(|operation| operation.loss.is_none()));
+        assert!((report.final_norm + loss.loss - 1.0).abs() < 1e-10);
+
+        let losses = propagation.get_losses();
+        assert_eq!(losses, vec![loss.loss]);
+    }
+
+    #[test]
+    fn test_report_json() {
+        let time_grid = TimeGrid { step: 25.0, step_no: 10, im_time: false };
+        let mut propagation = absorbed_propagation(&time_grid);
+        let report = propagation.propagate();
+
+        let json = report.to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        for key in ["step_no", "operations", "final_norm", "elapsed", "stop"] {
+            assert!(value.get(key).is_some(), "missing key {key}");
+        }
+        for key in ["propagators", "transformations", "savers", "controls"] {
+            assert!(value["elapsed"].get(key).is_some(), "missing elapsed key {key}");
+        }
+
+        let loaded: PropagationReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.step_no, report.step_no);
+        assert_eq!(loaded.final_norm, report.final_norm);
+        assert_eq!(loaded.operations.len(), report.operations.len());
+        assert_eq!(loaded.operations[0].loss.as_ref().unwrap().loss, report.operations[0].loss.as_ref().unwrap().loss);
+    }
+
+    #[test]
+    fn test_elapsed_times() {
+        let mut elapsed = ElapsedTimes::default();
+        elapsed.add(OperationKind::Propagator, Duration::from_millis(1500));
+        elapsed.add(OperationKind::Propagator, Duration::from_millis(500));
+        elapsed.add(OperationKind::Saver, Duration::from_millis(250));
+
+        assert_eq!(elapsed.propagators, 2.0);
+        assert_eq!(elapsed.transformations, 0.0);
+        assert_eq!(elapsed.savers, 0.25);
+        assert_eq!(elapsed.controls, 0.0);
+    }
+
+    #[test]
+    fn test_reporter_calls() {
+        let time_grid = TimeGrid { step: 25.0, step_no: 10, im_time: false };
+        let mut propagation = absorbed_propagation(&time_grid);
+
+        let calls = Arc::new(Mutex::new(Calls::default()));
+        propagation.set_reporter(Box::new(RecordingReporter { calls: calls.clone() }));
+        propagation.propagate();
+
+        let calls = calls.lock().unwrap();
+        let expected: Vec<(usize, f64)> = (0..10).map(|i| (i, 25.0 * i as f64)).collect();
+        assert_eq!(calls.steps, expected);
+        assert_eq!(calls.finished, vec![10]);
+    }
+
+    #[test]
+    fn test_print_reporter() {
+        let time_grid = TimeGrid { step: 25.0, step_no: 4, im_time: false };
+        let mut propagation = absorbed_propagation(&time_grid);
+        let buffer = SharedBuffer::default();
+        propagation.set_reporter(Box::new(PrintReporter::with_writer(2, buffer.clone())));
+
+        let report = propagation.propagate();
+        assert_eq!(report.step_no, 4);
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("step no: 0, time: 0,"));
+        assert!(lines[1].starts_with("step no: 2, time: 50,"));
+        assert_eq!(lines[2], format!("border loss: {}", report.operations[0].loss.as_ref().unwrap().loss));
+        assert_eq!(lines[3], format!("final norm: {}", report.final_norm));
+    }
+
+    #[test]
+    #[should_panic(expected = "Frequency has to be positive")]
+    fn test_print_reporter_zero_frequency() {
+        PrintReporter::new(0);
+    }
+}
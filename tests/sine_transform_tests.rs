@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod sine_transform_tests {
+    use std::f64::consts::PI;
+
+    use ndarray::{Array1, Array2, ArrayD};
+    use num::complex::Complex64;
+    use quantum::{
+        particle::Particle,
+        particles::Particles,
+        units::{
+            energy_units::{Energy, Kelvin},
+            mass_units::{Dalton, Mass},
+        },
+    };
+    use split_operator::{
+        grid::Grid,
+        hamiltonian_factory::kinetic_operator::sine_kinetic_hamiltonian,
+        propagator::{
+            dct_transformation::DCTTransformation, dst_transformation::DSTTransformation,
+            one_dim_propagator::OneDimPropagator, propagator_factory::one_dim_into_propagator,
+            transformation::Transformation, Propagator,
+        },
+        time_grid::{TimeGrid, TimeStep},
+        wave_function::WaveFunction,
+    };
+
+    fn test_array(x_grid: &Grid, y_grid: &Grid) -> ArrayD<Complex64> {
+        Array2::from_shape_fn((x_grid.nodes_no, y_grid.nodes_no), |(i, j)| {
+            let (x, y) = (x_grid.nodes[i], y_grid.nodes[j]);
+            Complex64::new((x * y).sin(), x * x - y)
+        })
+        .into_dyn()
+    }
+
+    fn assert_roundtrip<T: Transformation>(mut transformation: T, x_grid: Grid, y_grid: Grid) {
+        let array = test_array(&x_grid, &y_grid);
+        let mut wave_function = WaveFunction::new(array.clone(), vec![x_grid, y_grid]);
+
+        let norm = wave_function.norm();
+        transformation.transform(&mut wave_function);
+        assert!((wave_function.norm() - norm).abs() < 1e-10 * norm);
+        transformation.inverse_transform(&mut wave_function);
+        assert!((wave_function.norm() - norm).abs() < 1e-10 * norm);
+
+        for (x, y) in wave_function.array().iter().zip(array.iter()) {
+            assert!((x - y).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let x_grid = Grid::new_linear_countable("x", 0.5, 3.0, 6, 0);
+        let y_grid = Grid::new_linear_countable("y", 0.1, 1.0, 10, 1);
+        assert_roundtrip(DSTTransformation::new(&y_grid, "k_y"), x_grid.clone(), y_grid.clone());
+        assert_roundtrip(DSTTransformation::new(&x_grid, "k_x"), x_grid, y_grid);
+
+        let x_grid = Grid::new_linear_continuos("x", 0.0, 3.0, 7, 0);
+        let y_grid = Grid::new_linear_continuos("y", 0.0, 1.0, 12, 1);
+        assert_roundtrip(DCTTransformation::new(&y_grid, "k_y"), x_grid.clone(), y_grid.clone());
+        assert_roundtrip(DCTTransformation::new(&x_grid, "k_x"), x_grid, y_grid);
+    }
+
+    #[test]
+    fn particle_in_box() {
+        let collision_params = Particles::new_pair(
+            Particle::new("a", Mass(1.0, Dalton)),
+            Particle::new("b", Mass(1.0, Dalton)),
+            Energy(1e-7, Kelvin),
+        );
+        let length = 10.0;
+        let nodes_no = 99;
+        let step = length / (nodes_no as f64 + 1.0);
+        let grid = Grid::new_linear_countable("r", step, length - step, nodes_no, 0);
+
+        let array = grid.nodes.iter()
+            .map(|r| Complex64::from((2.0 * PI * r / length).sin()))
+            .collect::<Array1<Complex64>>()
+            .into_dyn();
+        let mut wave_function = WaveFunction::new(array.clone(), vec![grid.clone()]);
+
+        let mut dst = DSTTransformation::new(&grid, "momentum");
+        dst.transform(&mut wave_function);
+        for (i, value) in wave_function.array().iter().enumerate() {
+            if i != 1 {
+                assert!(value.norm() < 1e-12);
+            }
+        }
+
+        let time_grid = TimeGrid { step: 100.0, step_no: 1, im_time: false };
+        let kinetic = sine_kinetic_hamiltonian(&grid, &collision_params);
+        let energy = kinetic[1];
+        let mut propagator: OneDimPropagator = one_dim_into_propagator(kinetic, &grid, &time_grid, TimeStep::Full);
+        propagator.apply(&mut wave_function, 0.0);
+        dst.inverse_transform(&mut wave_function);
+
+        let phase = Complex64::new(0.0, -energy * time_grid.step).exp();
+        for (x, y) in wave_function.array().iter().zip(array.iter()) {
+            assert!((x - y * phase).norm() < 1e-12);
+        }
+    }
+}
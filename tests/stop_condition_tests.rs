@@ -0,0 +1,117 @@
+mod common;
+
+#[cfg(test)]
+mod stop_condition_tests {
+    use std::time::Duration;
+
+    use ndarray::Array1;
+    use quantum::{
+        particle_factory::create_atom,
+        particles::Particles,
+        units::energy_units::{Energy, Kelvin},
+    };
+    use split_operator::{
+        hamiltonian_factory::{analytic_potentials::harmonic, kinetic_operator::kinetic_hamiltonian},
+        propagation::{OperationStack, Propagation},
+        propagator::{fft_transformation::FFTTransformation, propagator_factory::one_dim_into_propagator, transformation::Order},
+        stop_condition::{NormThreshold, ObservableConvergence, RegionDensity, WallClock},
+        time_grid::{TimeGrid, TimeStep},
+        wave_function::WaveFunction,
+    };
+
+    use crate::common::{harmonic_grid, wave_packet};
+
+    /// Propagation of the Li6 - Li7 wave packet in the harmonic trap.
+    fn trap_propagation(time_grid: &TimeGrid) -> Propagation {
+        let grid = harmonic_grid();
+        let collision_params = Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        );
+        let potential: Array1<f64> = grid.nodes
+            .iter()
+            .map(|&x| harmonic(x, 0.0, collision_params.red_mass(), 0.001))
+            .collect();
+        let kinetic = kinetic_hamiltonian(&grid, &collision_params);
+
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(potential, &grid, time_grid, TimeStep::Half)));
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grid, "momentum")), Order::Normal);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(kinetic, &grid, time_grid, TimeStep::Full)));
+
+        Propagation::new(wave_packet(2.0), time_grid.clone(), operation_stack)
+    }
+
+    #[test]
+    fn test_without_conditions() {
+        let report = trap_propagation(&TimeGrid { step: 50.0, step_no: 300, im_time: false }).propagate();
+
+        assert_eq!(report.step_no, 300);
+        assert!(report.stop.is_none());
+    }
+
+    #[test]
+    fn test_norm_threshold() {
+        let mut propagation = trap_propagation(&TimeGrid { step: 50.0, step_no: 300, im_time: true });
+        propagation.add_stop_condition(Box::new(NormThreshold::new(0.5)), 1);
+        let report = propagation.propagate();
+
+        let stop = report.stop.unwrap();
+        assert_eq!(stop.condition, "NormThreshold");
+        assert!(report.step_no < 300);
+        assert_eq!(stop.step_no, report.step_no);
+        assert!((stop.time - 50.0 * report.step_no as f64).abs() < 1e-10);
+        assert!(report.final_norm < 0.5);
+    }
+
+    #[test]
+    fn test_observable_convergence() {
+        let mut propagation = trap_propagation(&TimeGrid { step: 50.0, step_no: 300, im_time: false });
+        propagation.add_stop_condition(Box::new(ObservableConvergence::new(|wf: &mut WaveFunction| wf.norm(), 1e-10)), 10);
+        let report = propagation.propagate();
+
+        assert_eq!(report.stop.unwrap().condition, "ObservableConvergence");
+        assert_eq!(report.step_no, 20);
+    }
+
+    #[test]
+    fn test_region_density() {
+        let mut propagation = trap_propagation(&TimeGrid { step: 50.0, step_no: 300, im_time: false });
+        let grids = propagation.wave_function().grids().to_vec();
+        let region = RegionDensity::new(&grids, |x| x[0] > 0.0, 1e-2);
+        let mut wave_function = propagation.wave_function().clone();
+        assert!(region.probability(&mut wave_function) > 0.9);
+
+        // the packet starts at x = 1 and leaves the region half an oscillation period later
+        propagation.add_stop_condition(Box::new(RegionDensity::new(&grids, |x| x[0] > 0.0, 1e-2)), 5);
+        propagation.add_stop_condition(Box::new(WallClock::new(Duration::from_secs(3600))), 1);
+        let report = propagation.propagate();
+
+        let stop = report.stop.unwrap();
+        assert_eq!(stop.condition, "RegionDensity");
+        assert_eq!(stop.step_no, report.step_no);
+        assert_eq!(stop.step_no % 5, 0);
+        assert!(stop.time > 1000.0 && stop.time < 3200.0, "{}", stop.time);
+
+        let mut wave_function = propagation.wave_function().clone();
+        assert!(region.probability(&mut wave_function) < 1e-2);
+    }
+
+    #[test]
+    fn test_region_density_and_wall_clock() {
+        let mut propagation = trap_propagation(&TimeGrid { step: 50.0, step_no: 300, im_time: false });
+        let grids = propagation.wave_function().grids().to_vec();
+        let region = RegionDensity::new(&grids, |x| x[0] > 3.5, 1e-3);
+        let mut wave_function = propagation.wave_function().clone();
+        assert!(region.probability(&mut wave_function) < 1e-3);
+
+        propagation.add_stop_condition(Box::new(region), 50);
+        propagation.add_stop_condition(Box::new(WallClock::new(Duration::ZERO)), 7);
+        let report = propagation.propagate();
+
+        let stop = report.stop.unwrap();
+        assert_eq!(stop.condition, "WallClock");
+        assert_eq!(stop.step_no, 7);
+    }
+}
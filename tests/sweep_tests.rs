@@ -0,0 +1,93 @@
+mod common;
+
+#[cfg(test)]
+mod sweep_tests {
+    use ndarray::Array1;
+    use quantum::{
+        particle_factory::create_atom,
+        particles::Particles,
+        units::energy_units::{Energy, Kelvin},
+    };
+    use split_operator::{
+        control::Apply,
+        hamiltonian_factory::{analytic_potentials::harmonic, kinetic_operator::kinetic_hamiltonian},
+        propagation::{OperationStack, Propagation},
+        propagator::{fft_transformation::FFTTransformation, propagator_factory::one_dim_into_propagator, transformation::Order},
+        sweep::{cartesian_product, Sweep},
+        time_grid::{TimeGrid, TimeStep},
+        wave_function_saver::StateSaver,
+    };
+
+    use crate::common::{harmonic_grid, wave_packet};
+
+    /// Propagation of the Li6 - Li7 wave packet with given `momentum` in the harmonic trap with given `frequency`,
+    /// saving 10 frames of the density as "position".
+    fn trap_propagation(frequency: f64, momentum: f64, time_grid: &TimeGrid) -> Propagation {
+        let grid = harmonic_grid();
+        let collision_params = Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        );
+        let potential: Array1<f64> = grid.nodes
+            .iter()
+            .map(|&x| harmonic(x, 0.0, collision_params.red_mass(), frequency))
+            .collect();
+        let kinetic = kinetic_hamiltonian(&grid, &collision_params);
+
+        let mut operation_stack = OperationStack::new();
+        let saver = StateSaver::new("position".to_string(), time_grid, &grid, 10);
+        operation_stack.add_saver(Box::new(saver), Apply::SecondHalf);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(potential, &grid, time_grid, TimeStep::Half)));
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grid, "momentum")), Order::Normal);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(kinetic, &grid, time_grid, TimeStep::Full)));
+
+        Propagation::new(wave_packet(momentum), time_grid.clone(), operation_stack)
+    }
+
+    #[test]
+    fn test_cartesian_product() {
+        let product = cartesian_product(&[1, 2], &["a", "b", "c"]);
+
+        assert_eq!(product.len(), 6);
+        assert_eq!(product[0], (1, "a"));
+        assert_eq!(product[4], (2, "b"));
+    }
+
+    #[test]
+    fn test_sweep() {
+        let parameters = cartesian_product(&[0.001, 0.002], &[0.0, 2.0, -1.0]);
+        let mut sweep = Sweep::new(parameters);
+        sweep.set_threads(2);
+
+        let dataset = sweep.run(|&(frequency, momentum)| {
+            assert!(momentum >= 0.0, "Negative momentum");
+            let time_grid = TimeGrid { step: 50.0, step_no: 100, im_time: false };
+            trap_propagation(frequency, momentum, &time_grid)
+        });
+
+        assert_eq!(dataset.members().len(), 6);
+        assert_eq!(dataset.successful().count(), 4);
+
+        let failed: Vec<_> = dataset.failed().collect();
+        assert_eq!(failed.len(), 2);
+        assert!(failed.iter().all(|(&(_, momentum), error)| momentum < 0.0 && *error == "Negative momentum"));
+
+        for (member, parameter) in dataset.members().iter().zip(sweep.parameters()) {
+            assert_eq!(&member.parameter, parameter);
+            if let Ok(result) = &member.result {
+                assert_eq!(result.report.step_no, 100);
+                assert!((result.report.final_norm - 1.0).abs() < 1e-10);
+            }
+        }
+
+        let densities = dataset.output("position");
+        assert!(densities[2].is_none());
+        assert_eq!(densities[0].unwrap().shape(), &[128, 10]);
+        assert_eq!(dataset.output("position_time")[1].unwrap().len(), 10);
+
+        let index = dataset.index_json("sweep");
+        assert!(index.contains("Negative momentum"));
+        assert!(index.contains("sweep_0_position.npy"));
+    }
+}
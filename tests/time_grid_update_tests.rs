@@ -0,0 +1,144 @@
+mod common;
+
+#[cfg(test)]
+mod time_grid_update_tests {
+    use ndarray::{Array1, ArrayD, IxDyn};
+    use num::complex::Complex64;
+    use quantum::{
+        particle_factory::create_atom,
+        particles::Particles,
+        units::energy_units::{Energy, Kelvin},
+    };
+    use split_operator::{
+        border_dumping::BorderDumping,
+        control::Control,
+        grid::Grid,
+        hamiltonian_factory::{analytic_potentials::harmonic, kinetic_operator::kinetic_hamiltonian},
+        propagation::{OperationStack, Propagation},
+        propagator::{
+            fft_transformation::FFTTransformation, propagator_factory::one_dim_into_propagator, transformation::Order,
+            Propagator,
+        },
+        time_grid::{TimeGrid, TimeStep},
+        wave_function::{gaussian_distribution, WaveFunction},
+    };
+
+    use crate::common::{harmonic_grid, wave_packet};
+
+    const OMEGA: f64 = 0.001;
+
+    fn test_wave_function(grid: &Grid) -> WaveFunction {
+        let array = ArrayD::from_shape_fn(IxDyn(&[grid.nodes_no]), |i| gaussian_distribution(grid.nodes[i[0]], 0.5, 0.3, 0.0));
+        let mut wave_function = WaveFunction::new(array, vec![grid.clone()]);
+        wave_function.normalize(1.0);
+
+        wave_function
+    }
+
+    #[test]
+    fn test_regenerated_propagator() {
+        let grid = Grid::new_linear_continuos("x", -1.0, 1.0, 32, 0);
+        let hamiltonian: Array1<f64> = grid.nodes.iter().map(|x| x * x).collect();
+        let factor = Array1::from_elem(grid.nodes_no, Complex64::new(0.5, 0.1));
+
+        let real_time = TimeGrid { step: 0.3, step_no: 1, im_time: false };
+        let im_time = TimeGrid { step: 0.7, step_no: 1, im_time: true };
+
+        let mut propagator = one_dim_into_propagator(hamiltonian.clone(), &grid, &real_time, TimeStep::Half);
+        propagator.add_operator(factor.clone());
+        propagator.update_time_grid(&real_time, &im_time);
+
+        let mut expected = one_dim_into_propagator(hamiltonian, &grid, &im_time, TimeStep::Half);
+        expected.add_operator(factor);
+
+        let mut wave_function = test_wave_function(&grid);
+        let mut expected_wave_function = wave_function.clone();
+        propagator.apply(&mut wave_function, 0.0);
+        expected.apply(&mut expected_wave_function, 0.0);
+
+        for (x, y) in wave_function.array().iter().zip(expected_wave_function.array().iter()) {
+            assert!((x - y).norm() < 1e-14);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Propagator explicit does not follow the time grid change")]
+    fn test_explicit_operator_time_grid_change() {
+        let grid = Grid::new_linear_continuos("x", -1.0, 1.0, 32, 0);
+        let im_time = TimeGrid { step: 0.7, step_no: 1, im_time: true };
+
+        let mut propagator = one_dim_into_propagator(Array1::zeros(grid.nodes_no), &grid, &im_time, TimeStep::Half);
+        propagator.set_operator(Array1::from_elem(grid.nodes_no, Complex64::from(0.9)));
+
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_propagator(Box::new(propagator));
+        operation_stack.name_last_operation("explicit");
+
+        let mut propagation = Propagation::new(test_wave_function(&grid), im_time, operation_stack);
+        propagation.set_time_grid(TimeGrid { step: 0.7, step_no: 1, im_time: false });
+    }
+
+    #[test]
+    fn test_scaled_mask() {
+        let grid = Grid::new_linear_continuos("x", -1.0, 1.0, 4, 0);
+        let mask = Array1::from_vec(vec![0.5, 1.0, 1.0, 0.0]).mapv(Complex64::from);
+        let mut dumping = BorderDumping::new(mask, &grid);
+        dumping.set_reference_step(1.0);
+
+        let previous = TimeGrid { step: 1.0, step_no: 1, im_time: false };
+        let time_grid = TimeGrid { step: 2.0, step_no: 1, im_time: false };
+        dumping.update_time_grid(&previous, &time_grid);
+
+        let mut wave_function = WaveFunction::new(ArrayD::ones(IxDyn(&[4])), vec![grid]);
+        dumping.first_half(&mut wave_function, 0.0);
+
+        let expected = [0.25, 1.0, 1.0, 0.0];
+        for (x, y) in wave_function.array().iter().zip(expected.iter()) {
+            assert!((x - y).norm() < 1e-14);
+        }
+    }
+
+    #[test]
+    fn test_relaxation_then_dynamics() {
+        let grid = harmonic_grid();
+        let collision_params = Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        );
+        let potential: Array1<f64> = grid.nodes
+            .iter()
+            .map(|&x| harmonic(x, 0.0, collision_params.red_mass(), OMEGA))
+            .collect();
+        let kinetic = kinetic_hamiltonian(&grid, &collision_params);
+
+        let im_time = TimeGrid { step: 20.0, step_no: 500, im_time: true };
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(potential, &grid, &im_time, TimeStep::Half)));
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grid, "momentum")), Order::Normal);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(kinetic, &grid, &im_time, TimeStep::Full)));
+
+        let mut propagation = Propagation::new(wave_packet(0.0), im_time, operation_stack);
+        propagation.propagate();
+
+        // relaxed state decays in imaginary time as exp(-2 E_0 t) with the ground state energy E_0 = omega / 2
+        let relaxed_norm = propagation.wave_function().clone().norm();
+        propagation.set_time_grid(TimeGrid { step: 20.0, step_no: 100, im_time: true });
+        let report = propagation.propagate();
+        let ground_energy = -(report.final_norm / relaxed_norm).ln() / (2.0 * 2000.0);
+        assert!((ground_energy / OMEGA - 0.5).abs() < 1e-4);
+
+        // relaxed state is stationary in the real time propagation with regenerated propagators
+        let mut relaxed = propagation.wave_function().clone();
+        propagation.set_time_grid(TimeGrid { step: 20.0, step_no: 200, im_time: false });
+        let report = propagation.propagate();
+        assert!((report.final_norm - relaxed.norm()).abs() / relaxed.norm() < 1e-10);
+
+        let density = relaxed.density();
+        let propagated = propagation.wave_function().clone().density();
+        let max_density = density.fold(0.0f64, |acc, &x| acc.max(x));
+        for (x, y) in density.iter().zip(propagated.iter()) {
+            assert!((x - y).abs() < 1e-3 * max_density);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+mod common;
+
+#[cfg(test)]
+mod time_schedule_tests {
+    use std::sync::{Arc, Mutex};
+
+    use ndarray::Array1;
+    use quantum::{
+        particle_factory::create_atom,
+        particles::Particles,
+        units::energy_units::{Energy, Kelvin},
+    };
+    use split_operator::{
+        border_dumping::{dumping_both, BorderDumping},
+        control::Apply,
+        hamiltonian_factory::{analytic_potentials::harmonic, kinetic_operator::kinetic_hamiltonian},
+        loss_checker::LossChecker,
+        propagation::{OperationStack, Propagation},
+        propagator::{fft_transformation::FFTTransformation, propagator_factory::one_dim_into_propagator, transformation::Order},
+        saver::Saver,
+        time_grid::{TimeGrid, TimeSchedule, TimeStep},
+        wave_function::WaveFunction,
+        wave_function_saver::StateSaver,
+    };
+
+    use crate::common::{harmonic_grid, wave_packet};
+
+    struct TimeRecorder {
+        times: Arc<Mutex<Vec<f64>>>,
+    }
+
+    impl Saver for TimeRecorder {
+        fn name(&self) -> &str {
+            "TimeRecorder"
+        }
+
+        fn monitor(&mut self, _wave_function: &mut WaveFunction, time: f64) {
+            self.times.lock().unwrap().push(time);
+        }
+
+        fn save(&self) -> Result<(), &str> {
+            Ok(())
+        }
+
+        fn reset(&mut self) {
+            self.times.lock().unwrap().clear();
+        }
+    }
+
+    /// Propagation of the Li6 - Li7 wave packet in the harmonic trap after the operations already added to `operation_stack`.
+    fn trap_propagation(time_grid: &TimeGrid, mut operation_stack: OperationStack) -> Propagation {
+        let grid = harmonic_grid();
+        let collision_params = Particles::new_pair(
+            create_atom("Li6").unwrap(),
+            create_atom("Li7").unwrap(),
+            Energy(1000.0, Kelvin),
+        );
+        let potential: Array1<f64> = grid.nodes
+            .iter()
+            .map(|&x| harmonic(x, 0.0, collision_params.red_mass(), 0.001))
+            .collect();
+        let kinetic = kinetic_hamiltonian(&grid, &collision_params);
+
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(potential, &grid, time_grid, TimeStep::Half)));
+        operation_stack.add_transformation(Box::new(FFTTransformation::new(&grid, "momentum")), Order::Normal);
+        operation_stack.add_propagator(Box::new(one_dim_into_propagator(kinetic, &grid, time_grid, TimeStep::Full)));
+
+        Propagation::new(wave_packet(2.0), time_grid.clone(), operation_stack)
+    }
+
+    fn recorded_propagation(time_grid: &TimeGrid) -> (Propagation, Arc<Mutex<Vec<f64>>>) {
+        let times = Arc::new(Mutex::new(Vec::new()));
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_saver(Box::new(TimeRecorder { times: times.clone() }), Apply::SecondHalf);
+
+        (trap_propagation(time_grid, operation_stack), times)
+    }
+
+    #[test]
+    fn test_from_steps() {
+        let schedule = TimeSchedule::from_steps(&[1.0, 1.0, 2.0, 2.0, 2.0, 1.0], false);
+
+        let segments: Vec<(f64, usize)> = schedule.segments()
+            .iter()
+            .map(|segment| (segment.step, segment.step_no))
+            .collect();
+        assert_eq!(segments, vec![(1.0, 2), (2.0, 3), (1.0, 1)]);
+        assert_eq!(schedule.step_no(), 6);
+        assert_eq!(schedule.duration(), 9.0);
+
+        let reversed = schedule.reversed();
+        assert_eq!(reversed.segments()[0].step, -1.0);
+        assert_eq!(reversed.duration(), -9.0);
+    }
+
+    #[test]
+    fn test_schedule_propagation() {
+        let fine = TimeGrid { step: 25.0, step_no: 40, im_time: false };
+        let coarse = TimeGrid { step: 50.0, step_no: 20, im_time: false };
+
+        let (mut scheduled, times) = recorded_propagation(&fine);
+        scheduled.set_time_schedule(TimeSchedule::new(vec![fine.clone(), coarse.clone()]));
+        let report = scheduled.propagate();
+        assert_eq!(report.step_no, 60);
+        assert!((scheduled.time() - 2000.0).abs() < 1e-10);
+
+        let times = times.lock().unwrap().clone();
+        assert_eq!(times.len(), 60);
+        assert!((times[0] - 25.0).abs() < 1e-10);
+        assert!((times[39] - 1000.0).abs() < 1e-10);
+        assert!((times[40] - 1050.0).abs() < 1e-10);
+
+        let (mut manual, _) = recorded_propagation(&fine);
+        manual.propagate();
+        manual.set_time_grid(coarse);
+        manual.propagate();
+        assert!((manual.time() - scheduled.time()).abs() < 1e-10);
+
+        for (x, y) in scheduled.wave_function().array().iter().zip(manual.wave_function().array().iter()) {
+            assert!((x - y).norm() < 1e-12);
+        }
+
+        scheduled.propagate_backward();
+        assert!(scheduled.time().abs() < 1e-10);
+        assert_eq!(scheduled.time_grid().step, 50.0);
+    }
+
+    #[test]
+    fn test_schedule_frames() {
+        let fine = TimeGrid { step: 25.0, step_no: 40, im_time: false };
+        let coarse = TimeGrid { step: 50.0, step_no: 20, im_time: false };
+
+        let schedule = TimeSchedule::new(vec![fine.clone(), coarse]);
+
+        // frames are spaced in time, so 4 frames are spread over the whole 2000 au of the schedule,
+        // in the coarse segment at the first steps after the frame is due
+        let saver = StateSaver::with_duration("position".to_string(), schedule.duration(), &harmonic_grid(), 4);
+        let mut dumping = BorderDumping::empty();
+        dumping.add_mask("x both", dumping_both(1.0, 0.5, &harmonic_grid()), &harmonic_grid());
+        dumping.add_loss_checker(LossChecker::new("dumping"));
+
+        let mut operation_stack = OperationStack::new();
+        operation_stack.add_saver(Box::new(saver), Apply::SecondHalf);
+        operation_stack.add_control(Box::new(dumping), Apply::FirstHalf | Apply::SecondHalf);
+
+        let mut propagation = trap_propagation(&fine, operation_stack);
+        propagation.set_time_schedule(schedule);
+        propagation.propagate();
+
+        let outputs = propagation.saver_outputs();
+        let (_, times) = outputs.iter().find(|(name, _)| name == "position_time").unwrap();
+        assert_eq!(times.as_slice().unwrap(), &[25.0, 525.0, 1050.0, 1550.0]);
+
+        // mean energy neither advances the time, monitors the savers nor accumulates losses
+        let wave_function = propagation.wave_function().clone();
+        let losses = propagation.get_losses();
+        assert!(losses[0] > 0.0);
+
+        let energy = propagation.mean_energy();
+        assert!(energy.is_finite());
+        assert!((propagation.time() - 2000.0).abs() < 1e-10);
+        assert_eq!(propagation.saver_outputs(), outputs);
+        assert_eq!(propagation.get_losses(), losses);
+        for (x, y) in wave_function.array().iter().zip(propagation.wave_function().array().iter()) {
+            assert_eq!(x, y);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod wave_function_tests {
+    use ndarray::{ArrayD, IxDyn};
+    use num::complex::Complex64;
+    use split_operator::{change_observer::ChangeObserver, grid::Grid, wave_function::WaveFunction};
+
+    fn constant_wave_function() -> WaveFunction {
+        let x_grid = Grid::new_linear_countable("x", 0.0, 9.0, 10, 0);
+        let y_grid = Grid::new_linear_countable("y", 0.0, 4.0, 5, 1);
+        let array = ArrayD::from_elem(IxDyn(&[10, 5]), Complex64::from(1.0));
+
+        WaveFunction::new(array, vec![x_grid, y_grid])
+    }
+
+    #[test]
+    fn test_array_mut_invalidates_norm() {
+        let mut wave_function = constant_wave_function();
+        wave_function.normalize(1.0);
+        assert!((wave_function.norm() - 1.0).abs() < 1e-12);
+
+        *wave_function.array_mut() *= Complex64::from(2.0);
+        assert!((wave_function.norm() - 4.0).abs() < 1e-12);
+
+        wave_function.array_mut()[[0, 0]] = Complex64::from(0.0);
+        assert!(wave_function.norm() < 4.0);
+    }
+
+    #[test]
+    fn test_grid_change_detection() {
+        let mut wave_function = constant_wave_function();
+        assert!((wave_function.norm() - 50.0).abs() < 1e-12);
+
+        let mut same_name = Grid::new_linear_countable("x", 0.0, 18.0, 10, 0);
+        wave_function.swap_grid(0, &mut same_name);
+        assert_eq!(same_name.weights, vec![1.0; 10]);
+        assert!((wave_function.norm() - 100.0).abs() < 1e-12);
+
+        wave_function.grids_mut()[1].weights = vec![0.5; 5];
+        assert!((wave_function.norm() - 50.0).abs() < 1e-12);
+        assert!((wave_function.weighted_density().sum() - 50.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_grid_generations() {
+        let grids = vec![
+            Grid::new_linear_countable("x", 0.0, 9.0, 10, 0),
+            Grid::new_linear_countable("y", 0.0, 4.0, 5, 1),
+        ];
+        let mut observer = ChangeObserver::new(&grids);
+        assert!(!observer.has_grid_changed(&grids));
+
+        observer.invalidate_grid(1);
+        assert!(observer.has_grid_changed(&grids));
+        observer.observe_grid(&grids);
+        assert!(!observer.has_grid_changed(&grids));
+
+        observer.invalidate_grids();
+        assert!(observer.has_grid_changed(&grids));
+        observer.observe_grid(&grids);
+
+        assert!(observer.has_grid_changed(&grids[..1]));
+        let resized = vec![grids[0].clone(), Grid::new_linear_countable("y", 0.0, 2.0, 3, 1)];
+        assert!(observer.has_grid_changed(&resized));
+    }
+
+    #[test]
+    fn test_shape_change() {
+        let mut wave_function = constant_wave_function();
+        assert!((wave_function.norm() - 50.0).abs() < 1e-12);
+
+        *wave_function.array_mut() = ArrayD::from_elem(IxDyn(&[10, 3]), Complex64::from(1.0));
+        *wave_function.grids_mut() = vec![
+            Grid::new_linear_countable("x", 0.0, 9.0, 10, 0),
+            Grid::new_linear_countable("z", 0.0, 2.0, 3, 1),
+        ];
+        assert!((wave_function.norm() - 30.0).abs() < 1e-12);
+        assert_eq!(wave_function.grids()[1].name, "z");
+    }
+}